@@ -1,76 +1,267 @@
 //! Contains everything required to analyze a hltas file.
 
-use std::{collections::HashMap, fmt::Display, str::FromStr};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    iter::Sum,
+    ops::{AddAssign, Range},
+    str::FromStr,
+};
 
 use hltas::{
-    types::{LeaveGroundActionType, Line},
+    types::{
+        ActionKeys, AutoActions, AutoMovement, FrameBulk, LeaveGroundAction,
+        LeaveGroundActionSpeed, LeaveGroundActionType, Line, MovementKeys, Properties, StrafeDir,
+        StrafeType, Times,
+    },
     HLTAS,
 };
 use num_bigint::BigUint;
 use rust_decimal::{
     prelude::{FromPrimitive, One, Zero},
-    Decimal,
+    Decimal, MathematicalOps,
 };
 use rust_decimal_macros::dec;
 use thiserror::Error;
 
-use ansi_term::Colour::*;
+use ansi_term::{ANSIString, Colour, Colour::*, Style};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Function that analyzes a HLTAS, returning a [`AnalyzerResult`][AnalyzerResult] type on success.
 /// - Only can fail if the frametime can't be parsed as a [`Decimal`](rust_decimal::Decimal).
-pub fn analyze_hltas(hltas: &HLTAS) -> Result<AnalyzerResult, Error> {
-    let mut final_time = FinalTime {
-        start: Decimal::ZERO,
-        end: Decimal::ZERO,
-    };
-    let mut estimated_time = Decimal::ZERO;
-    let mut frametime_stats = HashMap::new();
-    let mut save_count = BigUint::zero();
-    let mut shared_seed_set_count = BigUint::zero();
-    let mut button_set_count = BigUint::zero();
-    let mut lgagst_min_speed_set_count = BigUint::zero();
-    let mut reset_count = BigUint::zero();
-    let mut comment_count = BigUint::zero();
-    let mut change_angle_count = BigUint::zero();
-    let mut target_yaw_override_count = BigUint::zero();
+pub fn analyze_hltas(hltas: &HLTAS) -> Result<AnalyzerResult, Error<'_>> {
+    analyze_hltas_with(hltas, &AnalyzerOptions::default())
+}
+
+/// Function that analyzes a HLTAS with the given [`AnalyzerOptions`][AnalyzerOptions], returning a [`AnalyzerResult`][AnalyzerResult] type on success.
+/// - Only can fail if the frametime can't be parsed as a [`Decimal`](rust_decimal::Decimal).
+pub fn analyze_hltas_with<'a>(
+    hltas: &'a HLTAS,
+    options: &AnalyzerOptions,
+) -> Result<AnalyzerResult, Error<'a>> {
+    let mut analyzer = Analyzer::new(&hltas.properties, options)?;
+
+    for (line_index, line) in hltas.lines.iter().enumerate() {
+        analyzer.line(line_index, line)?;
+    }
+
+    Ok(analyzer.finish())
+}
+
+/// Function that analyzes a HLTAS like [`analyze_hltas_with`][analyze_hltas_with], but keeps going past errors.
+/// - Framebulks that fail to parse are left out of the analysis, giving a partial [`AnalyzerResult`][AnalyzerResult] of the other lines.
+/// - If the 0ms frametime property fails to parse, the default 0ms frametime is used instead.
+/// - Every error is returned in line order, with the location of each.
+pub fn analyze_hltas_keep_going(hltas: &HLTAS, options: &AnalyzerOptions) -> PartialAnalysis {
+    let mut errors = Vec::new();
+
+    let zero_ms_frametime = Analyzer::zero_ms_frametime(&hltas.properties).unwrap_or_else(|err| {
+        errors.push(err.into_owned());
+        Analyzer::DEFAULT_ZERO_MS_FRAMETIME
+    });
+    let mut analyzer = Analyzer::with_zero_ms_frametime(zero_ms_frametime, options);
+
+    for (line_index, line) in hltas.lines.iter().enumerate() {
+        if let Err(err) = analyzer.line(line_index, line) {
+            errors.push(err.into_owned());
+        }
+    }
+
+    PartialAnalysis {
+        result: analyzer.finish(),
+        errors,
+    }
+}
 
+/// Analysis of a HLTAS that kept going past errors, from [`analyze_hltas_keep_going`][analyze_hltas_keep_going].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartialAnalysis {
+    /// The analysis of the lines that didn't fail.
+    /// - Same as the result of [`analyze_hltas_with`][analyze_hltas_with] if there are no errors.
+    pub result: AnalyzerResult,
+    /// Every error of the analysis, in line order.
+    pub errors: Vec<OwnedError>,
+}
+
+impl PartialAnalysis {
+    /// Returns the result if there are no errors, or all of the errors.
+    pub fn into_result(self) -> Result<AnalyzerResult, Vec<OwnedError>> {
+        if self.errors.is_empty() {
+            Ok(self.result)
+        } else {
+            Err(self.errors)
+        }
+    }
+}
+
+/// Accumulates the analysis of HLTAS lines, one line at a time.
+pub(crate) struct Analyzer {
+    final_time: FinalTime,
+    estimated_time: Decimal,
+    frametime_stats: HashMap<Decimal, BigUint>,
+    save_count: BigUint,
+    shared_seed_set_count: BigUint,
+    button_set_count: BigUint,
+    lgagst_min_speed_set_count: BigUint,
+    reset_count: BigUint,
+    comment_count: BigUint,
+    change_angle_count: BigUint,
+    target_yaw_override_count: BigUint,
+    timeline: Vec<FrameBulkTimeline>,
+    auto_action_stats: AutoActionStats,
+    strafe_stats: StrafeStats,
+    key_stats: KeyStats,
+    // keys of the previous framebulk, used to count key presses
+    previous_keys: (MovementKeys, ActionKeys),
+    console_command_stats: Vec<ConsoleCommandStats>,
+    // index of the command name in `console_command_stats`
+    console_command_indices: HashMap<String, usize>,
+    total_frame_count: BigUint,
+    // the framebulk on the previous line, used to find mergeable framebulks
+    previous_framebulk: Option<(usize, FrameBulk)>,
+    mergeable_framebulks: Vec<Range<usize>>,
     // used for tracking the 0ms frame estimation
-    let mut zero_ms_counter = Decimal::ZERO;
+    zero_ms_counter: Decimal,
+    zero_ms_frametime: Decimal,
+    // 0ms ducktaps left from a limited 0ms ducktap framebulk
+    zero_ms_ducktaps_left: u32,
+    // 0ms frames left for the minimum final time, separate from the estimate as it doesn't depend on the airtime
+    zero_ms_frames_left: u32,
+    ducktap_airtime: Decimal,
+    // used to switch the ducktap airtime on map changes
+    options: AnalyzerOptions,
+}
 
-    let zero_ms_frametime = match &hltas.properties.frametime_0ms {
-        Some(zero_ms) => {
-            Decimal::from_str(zero_ms).map_err(|err| Error::ZeroMsFrametimeParseError {
-                source: err,
-                string: zero_ms,
-            })?
+impl Analyzer {
+    /// The 0ms frametime if the `frametime0ms` property isn't set.
+    const DEFAULT_ZERO_MS_FRAMETIME: Decimal = Decimal::from_parts(1, 0, 0, false, 10);
+
+    pub(crate) fn new<'a>(
+        properties: &'a Properties,
+        options: &AnalyzerOptions,
+    ) -> Result<Self, Error<'a>> {
+        Ok(Self::with_zero_ms_frametime(
+            Self::zero_ms_frametime(properties)?,
+            options,
+        ))
+    }
+
+    /// The 0ms frametime from the properties.
+    fn zero_ms_frametime(properties: &Properties) -> Result<Decimal, Error<'_>> {
+        match &properties.frametime_0ms {
+            Some(zero_ms) => {
+                Decimal::from_str(zero_ms).map_err(|err| Error::ZeroMsFrametimeParseError {
+                    source: err,
+                    string: zero_ms,
+                })
+            }
+            None => Ok(Self::DEFAULT_ZERO_MS_FRAMETIME),
         }
-        None => Decimal::new(1, 10),
-    };
+    }
 
-    for line in &hltas.lines {
+    fn with_zero_ms_frametime(zero_ms_frametime: Decimal, options: &AnalyzerOptions) -> Self {
+        Self {
+            final_time: FinalTime {
+                start: Decimal::ZERO,
+                end: Decimal::ZERO,
+            },
+            estimated_time: Decimal::ZERO,
+            frametime_stats: HashMap::new(),
+            save_count: BigUint::zero(),
+            shared_seed_set_count: BigUint::zero(),
+            button_set_count: BigUint::zero(),
+            lgagst_min_speed_set_count: BigUint::zero(),
+            reset_count: BigUint::zero(),
+            comment_count: BigUint::zero(),
+            change_angle_count: BigUint::zero(),
+            target_yaw_override_count: BigUint::zero(),
+            timeline: Vec::new(),
+            auto_action_stats: AutoActionStats::default(),
+            strafe_stats: StrafeStats::default(),
+            key_stats: KeyStats::default(),
+            previous_keys: Default::default(),
+            console_command_stats: Vec::new(),
+            console_command_indices: HashMap::new(),
+            total_frame_count: BigUint::zero(),
+            previous_framebulk: None,
+            mergeable_framebulks: Vec::new(),
+            zero_ms_counter: Decimal::ZERO,
+            zero_ms_frametime,
+            zero_ms_ducktaps_left: 0,
+            zero_ms_frames_left: 0,
+            ducktap_airtime: options.ducktap_airtime(),
+            options: options.clone(),
+        }
+    }
+
+    /// Adds a line to the analysis.
+    /// - `line_index` is the index of the line in the HLTAS, used for the timeline.
+    pub(crate) fn line<'a>(&mut self, line_index: usize, line: &'a Line) -> Result<(), Error<'a>> {
         match line {
             Line::FrameBulk(fb) => {
-                let zero_ms_ducktap = if let Some(action) = &fb.auto_actions.leave_ground_action {
-                    if let LeaveGroundActionType::DuckTap { zero_ms } = &action.type_ {
-                        *zero_ms
-                    } else {
-                        false
-                    }
-                } else {
-                    false
-                };
-
+                // parsed first, so a failed framebulk doesn't change the analysis
                 let frame_time =
                     fb.frame_time
                         .parse::<Decimal>()
                         .map_err(|err| Error::FrametimeParseError {
                             source: err,
                             string: &fb.frame_time,
+                            line_index,
                         })?;
+                // how many 0ms ducktaps the framebulk can do, `None` being unlimited
+                let zero_ms_ducktaps = match &fb.auto_actions.leave_ground_action {
+                    Some(LeaveGroundAction {
+                        type_: LeaveGroundActionType::DuckTap { zero_ms: true },
+                        times,
+                        ..
+                    }) => match times {
+                        Times::UnlimitedWithinFrameBulk => {
+                            self.zero_ms_ducktaps_left = 0;
+                            self.zero_ms_frames_left = 0;
+                            None
+                        }
+                        Times::Limited(times) => {
+                            self.zero_ms_ducktaps_left = times.get();
+                            self.zero_ms_frames_left = times.get();
+                            Some(times.get())
+                        }
+                    },
+                    Some(_) => {
+                        self.zero_ms_ducktaps_left = 0;
+                        self.zero_ms_frames_left = 0;
+                        Some(0)
+                    }
+                    // limited actions carry over to framebulks without an action
+                    None => Some(self.zero_ms_ducktaps_left),
+                };
+                let zero_ms_ducktap = zero_ms_ducktaps != Some(0);
+
                 // shouldn't be a negative value
                 let frame_count = BigUint::from_u32(fb.frame_count.get()).unwrap();
 
-                frametime_stats
+                self.auto_action_stats
+                    .add_framebulk(&fb.auto_actions, &frame_count);
+                self.strafe_stats
+                    .add_framebulk(&fb.auto_actions, &frame_count);
+                let keys = (fb.movement_keys, fb.action_keys);
+                self.key_stats
+                    .add_framebulk(&keys, &self.previous_keys, &frame_count);
+                self.previous_keys = keys;
+
+                if let Some(console_command) = &fb.console_command {
+                    self.console_command(line_index, console_command);
+
+                    // the map changes before the framebulk's frames run
+                    if let Some(map) = map_change(console_command) {
+                        if !self.options.map_ducktap_airtimes.is_empty() {
+                            self.ducktap_airtime = self.options.map_ducktap_airtime(map);
+                        }
+                    }
+                }
+
+                self.frametime_stats
                     .entry(frame_time)
                     .and_modify(|count: &mut BigUint| {
                         *count += &frame_count;
@@ -80,23 +271,41 @@ pub fn analyze_hltas(hltas: &HLTAS) -> Result<AnalyzerResult, Error> {
                 // add final time range
                 let fb_time = frame_time * Decimal::from(fb.frame_count.get());
 
-                if !zero_ms_ducktap {
-                    final_time.start += fb_time;
+                match zero_ms_ducktaps {
+                    // all frames can be 0ms
+                    None => (),
+                    // only as many frames as the limited 0ms ducktaps left can be 0ms
+                    Some(_) => {
+                        let zero_ms_frames = fb.frame_count.get().min(self.zero_ms_frames_left);
+                        self.zero_ms_frames_left -= zero_ms_frames;
+
+                        let non_zero_ms_frames = fb.frame_count.get() - zero_ms_frames;
+                        self.final_time.start += frame_time * Decimal::from(non_zero_ms_frames);
+                    }
                 }
-                final_time.end += fb_time;
+                self.final_time.end += fb_time;
 
                 // add estimated time
-                estimated_time += if zero_ms_ducktap {
-                    // simulate flat ground 0ms ducktap
-                    // 0.201s to reach the ground, then the next frame becomes 0ms
+                self.estimated_time += if zero_ms_ducktap {
+                    // simulate 0ms ducktap with the landing model from the options
+                    // the airtime is spent to reach the ground, then the next frame becomes 0ms
                     let mut fb_time_with_zero_ms = Decimal::ZERO;
 
                     for _ in 0..fb.frame_count.get() {
-                        zero_ms_counter += frame_time;
+                        self.zero_ms_counter += frame_time;
 
-                        if zero_ms_counter > dec!(0.201) {
-                            zero_ms_counter = Decimal::ZERO;
-                            fb_time_with_zero_ms += zero_ms_frametime;
+                        let can_zero_ms = match zero_ms_ducktaps {
+                            None => true,
+                            Some(_) => self.zero_ms_ducktaps_left > 0,
+                        };
+
+                        if can_zero_ms && self.zero_ms_counter > self.ducktap_airtime {
+                            self.zero_ms_counter = Decimal::ZERO;
+                            fb_time_with_zero_ms += self.zero_ms_frametime;
+
+                            if zero_ms_ducktaps.is_some() {
+                                self.zero_ms_ducktaps_left -= 1;
+                            }
                         } else {
                             fb_time_with_zero_ms += frame_time;
                         }
@@ -106,64 +315,249 @@ pub fn analyze_hltas(hltas: &HLTAS) -> Result<AnalyzerResult, Error> {
                 } else {
                     fb_time
                 };
+
+                let start_frame = self.total_frame_count.clone();
+                self.total_frame_count += fb.frame_count.get();
+
+                self.timeline.push(FrameBulkTimeline {
+                    line_index,
+                    start_frame,
+                    end_frame: self.total_frame_count.clone(),
+                    final_time: self.final_time,
+                    estimated_time: self.estimated_time,
+                    zero_ms_ducktap,
+                });
+
+                // only framebulks right after each other can be merged
+                if let Some((previous_line_index, previous)) = &self.previous_framebulk {
+                    if previous_line_index + 1 == line_index && framebulks_mergeable(previous, fb) {
+                        match self.mergeable_framebulks.last_mut() {
+                            Some(run) if run.end == line_index => run.end = line_index + 1,
+                            _ => self
+                                .mergeable_framebulks
+                                .push(*previous_line_index..line_index + 1),
+                        }
+                    }
+                }
+                self.previous_framebulk = Some((line_index, fb.clone()));
             }
-            Line::Save(_) => save_count += BigUint::one(),
-            Line::SharedSeed(_) => shared_seed_set_count += BigUint::one(),
-            Line::Buttons(_) => button_set_count += BigUint::one(),
-            Line::LGAGSTMinSpeed(_) => lgagst_min_speed_set_count += BigUint::one(),
-            Line::Reset { .. } => reset_count += BigUint::one(),
-            Line::Comment(_) => comment_count += BigUint::one(),
+            Line::Save(_) => self.save_count += BigUint::one(),
+            Line::SharedSeed(_) => self.shared_seed_set_count += BigUint::one(),
+            Line::Buttons(_) => self.button_set_count += BigUint::one(),
+            Line::LGAGSTMinSpeed(_) => self.lgagst_min_speed_set_count += BigUint::one(),
+            Line::Reset { .. } => self.reset_count += BigUint::one(),
+            Line::Comment(_) => self.comment_count += BigUint::one(),
             Line::VectorialStrafing(_) => (),
             Line::VectorialStrafingConstraints(_) => (),
-            Line::Change(_) => change_angle_count += BigUint::one(),
-            Line::TargetYawOverride(_) => target_yaw_override_count += BigUint::one(),
+            Line::RenderYawOverride(_) => (),
+            Line::PitchOverride(_) => (),
+            Line::RenderPitchOverride(_) => (),
+            Line::Change(_) => self.change_angle_count += BigUint::one(),
+            Line::TargetYawOverride(_) => self.target_yaw_override_count += BigUint::one(),
+        }
+
+        Ok(())
+    }
+
+    fn console_command(&mut self, line_index: usize, console_command: &str) {
+        for name in console_command_names(console_command) {
+            let index = *self
+                .console_command_indices
+                .entry(name.to_string())
+                .or_insert_with(|| {
+                    self.console_command_stats.push(ConsoleCommandStats {
+                        name: name.to_string(),
+                        count: BigUint::zero(),
+                        line_indices: Vec::new(),
+                    });
+                    self.console_command_stats.len() - 1
+                });
+            let stats = &mut self.console_command_stats[index];
+
+            stats.count += BigUint::one();
+            if stats.line_indices.last() != Some(&line_index) {
+                stats.line_indices.push(line_index);
+            }
         }
     }
 
-    let frametime_stats = {
-        let mut frametime_stats_res = Vec::new();
+    /// Finishes the analysis, returning the result.
+    pub(crate) fn finish(self) -> AnalyzerResult {
+        let frametime_stats = {
+            let mut frametime_stats_res = Vec::new();
+
+            for (s, v) in self.frametime_stats {
+                frametime_stats_res.push(FrametimeStats {
+                    frametime: s,
+                    frame_count: v,
+                });
+            }
 
-        for (s, v) in frametime_stats {
-            frametime_stats_res.push(FrametimeStats {
-                frametime: s,
-                frame_count: v,
-            });
+            frametime_stats_res.sort_by_key(|f| f.frametime);
+
+            frametime_stats_res
+        };
+
+        AnalyzerResult {
+            final_time: self.final_time,
+            estimated_time: self.estimated_time,
+            frametime_stats,
+            save_count: self.save_count,
+            shared_seed_set_count: self.shared_seed_set_count,
+            button_set_count: self.button_set_count,
+            lgagst_min_speed_set_count: self.lgagst_min_speed_set_count,
+            reset_count: self.reset_count,
+            comment_count: self.comment_count,
+            change_angle_count: self.change_angle_count,
+            target_yaw_override_count: self.target_yaw_override_count,
+            timeline: self.timeline,
+            auto_action_stats: self.auto_action_stats,
+            strafe_stats: self.strafe_stats,
+            key_stats: self.key_stats,
+            console_command_stats: self.console_command_stats,
+            mergeable_framebulks: self.mergeable_framebulks,
         }
+    }
+}
 
-        frametime_stats_res.sort_by(|f, s| f.frametime.cmp(&s.frametime));
+/// Checks if two framebulks in a row can be merged into one framebulk without changing the behaviour.
+/// - The framebulks must be the same other than the frame count.
+/// - Framebulks with a console command or an automatic action limited to some times can't be merged,
+///   as the command or the times would happen once per framebulk.
+pub fn framebulks_mergeable(a: &FrameBulk, b: &FrameBulk) -> bool {
+    let limited_times = |fb: &FrameBulk| {
+        let auto_actions = &fb.auto_actions;
 
-        frametime_stats_res
+        [
+            auto_actions.leave_ground_action.map(|action| action.times),
+            auto_actions.jump_bug.map(|action| action.times),
+            auto_actions
+                .duck_before_collision
+                .map(|action| action.times),
+            auto_actions.duck_before_ground.map(|action| action.times),
+            auto_actions.duck_when_jump.map(|action| action.times),
+        ]
+        .into_iter()
+        .any(|times| matches!(times, Some(Times::Limited(_))))
     };
 
-    Ok(AnalyzerResult {
-        final_time,
-        estimated_time,
-        frametime_stats,
-        save_count,
-        shared_seed_set_count,
-        button_set_count,
-        lgagst_min_speed_set_count,
-        reset_count,
-        comment_count,
-        change_angle_count,
-        target_yaw_override_count,
+    a.console_command.is_none()
+        && !limited_times(a)
+        && a == &FrameBulk {
+            frame_count: a.frame_count,
+            ..b.clone()
+        }
+}
+
+/// Splits a framebulk console command on `;`, returning the name of each command.
+/// - The name is the first word of the command, for example `changelevel` for `changelevel c1a0 c1a0a`.
+pub fn console_command_names(console_command: &str) -> impl Iterator<Item = &str> {
+    console_command
+        .split(';')
+        .filter_map(|command| command.split_whitespace().next())
+}
+
+/// Finds the map a console command changes to, from `map`, `changelevel` or `changelevel2` commands.
+/// - `changelevel2` is what the game runs for level transitions from `trigger_changelevel`.
+/// - If there are multiple map changes, the last one is returned.
+pub fn map_change(console_command: &str) -> Option<&str> {
+    console_command.split(';').rev().find_map(|command| {
+        let mut words = command.split_whitespace();
+
+        match words.next()? {
+            "map" | "changelevel" | "changelevel2" => words.next().map(|map| map.trim_matches('"')),
+            _ => None,
+        }
     })
 }
 
+/// Options for the [`analyze_hltas_with`][analyze_hltas_with] function.
+/// - The default options are for landing on flat ground with normal gravity.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AnalyzerOptions {
+    /// The `sv_gravity` value, in units per second squared.
+    pub sv_gravity: Decimal,
+    /// The height the player gets lifted by a ducktap, in units.
+    /// - The default is tuned to give the 0.201s airtime of a flat ground ducktap with normal gravity.
+    pub ducktap_height: Decimal,
+    /// The distance the player falls below the ground the ducktap was done on, in units.
+    /// - Negative for landing higher, for example when ducktapping up stairs.
+    pub fall_distance: Decimal,
+    /// A measured ducktap airtime in seconds, used instead of deriving it from the other options.
+    /// - See [`Calibration`](crate::calibration::Calibration) for measuring it from bxt logs.
+    pub calibrated_ducktap_airtime: Option<Decimal>,
+    /// Measured ducktap airtimes of maps in seconds, switched to at framebulks changing the map.
+    /// - Maps without an airtime use the one derived from the other options.
+    /// - If empty, the airtime doesn't change on map changes.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub map_ducktap_airtimes: BTreeMap<String, Decimal>,
+}
+
+impl Default for AnalyzerOptions {
+    fn default() -> Self {
+        Self {
+            sv_gravity: dec!(800),
+            ducktap_height: dec!(16.1604),
+            fall_distance: Decimal::ZERO,
+            calibrated_ducktap_airtime: None,
+            map_ducktap_airtimes: BTreeMap::new(),
+        }
+    }
+}
+
+impl AnalyzerOptions {
+    /// The airtime of a ducktap before the next 0ms frame, in seconds.
+    /// - Is `calibrated_ducktap_airtime` if it's set.
+    /// - Otherwise derived from the time it takes to fall `ducktap_height + fall_distance` with `sv_gravity`.
+    /// - Is zero if the player wouldn't fall at all.
+    pub fn ducktap_airtime(&self) -> Decimal {
+        match self.calibrated_ducktap_airtime {
+            Some(airtime) => airtime.max(Decimal::ZERO),
+            None => self.derived_ducktap_airtime(),
+        }
+    }
+
+    /// The airtime of a ducktap after changing to the map, in seconds.
+    /// - Is the airtime of the map in `map_ducktap_airtimes` if it has one.
+    /// - Otherwise derived like [`ducktap_airtime`][AnalyzerOptions::ducktap_airtime] without a calibrated airtime.
+    pub fn map_ducktap_airtime(&self, map: &str) -> Decimal {
+        match self.map_ducktap_airtimes.get(map).copied() {
+            Some(airtime) => airtime.max(Decimal::ZERO),
+            None => self.derived_ducktap_airtime(),
+        }
+    }
+
+    fn derived_ducktap_airtime(&self) -> Decimal {
+        let height = self.ducktap_height + self.fall_distance;
+
+        if height <= Decimal::ZERO || self.sv_gravity <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+
+        (dec!(2) * height / self.sv_gravity)
+            .sqrt()
+            .unwrap_or(Decimal::ZERO)
+    }
+}
+
 /// Error type for the [`analyze_hltas`][analyze_hltas] function.
-#[derive(Debug, Error)]
+/// - Borrows the string that failed to parse from the HLTAS, use [`Error::into_owned`][Error::into_owned] to get an [`OwnedError`][OwnedError].
+#[derive(Clone, Debug, PartialEq, Error)]
 pub enum Error<'a> {
     /// Error when parsing a frametime string.
     /// Happens if the frametime can't be parse as a [`Decimal`](rust_decimal::Decimal).
-    #[error("Failed to parse frametime {string} as a decimal")]
+    #[error("Failed to parse frametime {string} as a decimal at line index {line_index}")]
     FrametimeParseError {
         #[source]
         source: rust_decimal::Error,
         string: &'a str,
+        /// The index of the framebulk in the HLTAS lines.
+        line_index: usize,
     },
     /// Error when parsing a 0ms frametime property from a string.
     /// Happens if the frametime can't be parse as a [`Decimal`](rust_decimal::Decimal).
-    #[error("Failed to parse 0ms frametime {string} as a decimal")]
+    #[error("Failed to parse 0ms frametime {string} as a decimal in properties")]
     ZeroMsFrametimeParseError {
         #[source]
         source: rust_decimal::Error,
@@ -171,38 +565,277 @@ pub enum Error<'a> {
     },
 }
 
+impl Error<'_> {
+    /// Where in the HLTAS the error happened.
+    pub fn location(&self) -> ErrorLocation {
+        match self {
+            Error::FrametimeParseError { line_index, .. } => ErrorLocation::Line(*line_index),
+            Error::ZeroMsFrametimeParseError { .. } => ErrorLocation::Properties,
+        }
+    }
+
+    /// Converts the error into an [`OwnedError`][OwnedError], which doesn't borrow from the HLTAS.
+    pub fn into_owned(self) -> OwnedError {
+        match self {
+            Error::FrametimeParseError {
+                source,
+                string,
+                line_index,
+            } => OwnedError::FrametimeParseError {
+                source,
+                string: string.to_string(),
+                line_index,
+            },
+            Error::ZeroMsFrametimeParseError { source, string } => {
+                OwnedError::ZeroMsFrametimeParseError {
+                    source,
+                    string: string.to_string(),
+                }
+            }
+        }
+    }
+}
+
+impl From<Error<'_>> for OwnedError {
+    fn from(err: Error<'_>) -> Self {
+        err.into_owned()
+    }
+}
+
+/// Owned version of [`Error`][enum@Error], which can outlive the HLTAS and be sent across threads.
+#[derive(Clone, Debug, PartialEq, Error)]
+pub enum OwnedError {
+    /// Error when parsing a frametime string.
+    /// Happens if the frametime can't be parse as a [`Decimal`](rust_decimal::Decimal).
+    #[error("Failed to parse frametime {string} as a decimal at line index {line_index}")]
+    FrametimeParseError {
+        #[source]
+        source: rust_decimal::Error,
+        string: String,
+        /// The index of the framebulk in the HLTAS lines.
+        line_index: usize,
+    },
+    /// Error when parsing a 0ms frametime property from a string.
+    /// Happens if the frametime can't be parse as a [`Decimal`](rust_decimal::Decimal).
+    #[error("Failed to parse 0ms frametime {string} as a decimal in properties")]
+    ZeroMsFrametimeParseError {
+        #[source]
+        source: rust_decimal::Error,
+        string: String,
+    },
+}
+
+impl OwnedError {
+    /// Where in the HLTAS the error happened.
+    pub fn location(&self) -> ErrorLocation {
+        match self {
+            OwnedError::FrametimeParseError { line_index, .. } => ErrorLocation::Line(*line_index),
+            OwnedError::ZeroMsFrametimeParseError { .. } => ErrorLocation::Properties,
+        }
+    }
+}
+
+/// Where in a HLTAS an analysis error happened.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ErrorLocation {
+    /// The properties of the HLTAS, such as `frametime0ms`.
+    Properties,
+    /// The line at this index in the HLTAS lines.
+    Line(usize),
+}
+
+impl Display for ErrorLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorLocation::Properties => write!(f, "properties"),
+            ErrorLocation::Line(line_index) => write!(f, "line index {line_index}"),
+        }
+    }
+}
+
 /// Analysis result of a HLTAS.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AnalyzerResult {
     /// The final time of the HLTAS.
     /// - `start` will be the shortest possible time of the hltas, assuming all 0ms ducktap framebulks are 0ms.
+    ///   With limited 0ms ducktaps, only as many frames as the ducktaps left can be 0ms.
     /// - `end` will be the longest possible time of the hltas, assuming all 0ms ducktap framebulks aren't 0ms.
     pub final_time: FinalTime,
     /// The estimated time of the HLTAS.
-    /// Assumes the 0ms ducktap framebulks are landing on a flat ground, with normal gravity.
+    /// Assumes the 0ms ducktap framebulks are landing as described by the [`AnalyzerOptions`][AnalyzerOptions],
+    /// which is flat ground with normal gravity by default.
     pub estimated_time: Decimal,
     /// The frametime stats of the HLTAS, containing `frametime` and total `frame_count`.
     pub frametime_stats: Vec<FrametimeStats>,
     /// The number of `save` special frames in the HLTAS.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_biguint"))]
     pub save_count: BigUint,
     /// The number of `shared_seed` sets in the HLTAS.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_biguint"))]
     pub shared_seed_set_count: BigUint,
     /// The number of strafing `button` mapping in the HLTAS, including resetting.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_biguint"))]
     pub button_set_count: BigUint,
     /// The number of `lgagst_min_speed` sets in the HLTAS.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_biguint"))]
     pub lgagst_min_speed_set_count: BigUint,
     /// The number of `reset` done in the HLTAS.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_biguint"))]
     pub reset_count: BigUint,
     /// The number of `comment` in the HLTAS.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_biguint"))]
     pub comment_count: BigUint,
     /// The number of `change` in the HLTAS.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_biguint"))]
     pub change_angle_count: BigUint,
     /// The number of `target_yaw_override` in the HLTAS.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_biguint"))]
     pub target_yaw_override_count: BigUint,
+    /// The timeline of the HLTAS, containing an entry for every framebulk in order.
+    /// - Not shown in the [`Display`](std::fmt::Display) output, as it would be as long as the HLTAS itself.
+    pub timeline: Vec<FrameBulkTimeline>,
+    /// The usage of automatic actions in the HLTAS framebulks.
+    pub auto_action_stats: AutoActionStats,
+    /// The usage of automatic strafing in the HLTAS framebulks.
+    pub strafe_stats: StrafeStats,
+    /// The usage of movement and action keys in the HLTAS framebulks.
+    pub key_stats: KeyStats,
+    /// The console commands used in the HLTAS framebulks, in order of first use.
+    pub console_command_stats: Vec<ConsoleCommandStats>,
+    /// The line ranges of runs of framebulks right after each other that could be merged into one framebulk.
+    /// - See [`framebulks_mergeable`][framebulks_mergeable] for which framebulks can be merged.
+    pub mergeable_framebulks: Vec<Range<usize>>,
+}
+
+impl AnalyzerResult {
+    /// The counts of special lines with their display names, in display order.
+    pub fn counts(&self) -> [(&'static str, &BigUint); 8] {
+        [
+            ("Save count", &self.save_count),
+            ("Shared seed set count", &self.shared_seed_set_count),
+            ("Button set count", &self.button_set_count),
+            (
+                "LGAGST min speed set count",
+                &self.lgagst_min_speed_set_count,
+            ),
+            ("Reset count", &self.reset_count),
+            ("Comment count", &self.comment_count),
+            ("Change angle count", &self.change_angle_count),
+            ("Target yaw override count", &self.target_yaw_override_count),
+        ]
+    }
+
+    /// The total number of frames in the HLTAS.
+    pub fn frame_count(&self) -> BigUint {
+        self.frametime_stats
+            .iter()
+            .map(|stats| &stats.frame_count)
+            .sum()
+    }
+
+    /// Finds the timeline entry for the given line index of the HLTAS.
+    /// - If the line isn't a framebulk, the entry of the last framebulk before it is returned.
+    /// - Returns `None` if there are no framebulks up to the line.
+    pub fn timeline_at_line(&self, line_index: usize) -> Option<&FrameBulkTimeline> {
+        let after = self
+            .timeline
+            .partition_point(|entry| entry.line_index <= line_index);
+
+        after.checked_sub(1).map(|i| &self.timeline[i])
+    }
+}
+
+/// Combines the analysis of another HLTAS into this one, as if it was played right after.
+/// - The timeline entries of `rhs` are offset by the frames and times of this analysis,
+///   but the line indices still refer to the lines of the other HLTAS.
+/// - The console command line indices also still refer to the lines of the other HLTAS.
+impl AddAssign<&AnalyzerResult> for AnalyzerResult {
+    fn add_assign(&mut self, rhs: &AnalyzerResult) {
+        let frame_count = self.frame_count();
+        let timeline = rhs.timeline.iter().map(|entry| FrameBulkTimeline {
+            line_index: entry.line_index,
+            start_frame: &entry.start_frame + &frame_count,
+            end_frame: &entry.end_frame + &frame_count,
+            final_time: FinalTime {
+                start: entry.final_time.start + self.final_time.start,
+                end: entry.final_time.end + self.final_time.end,
+            },
+            estimated_time: entry.estimated_time + self.estimated_time,
+            zero_ms_ducktap: entry.zero_ms_ducktap,
+        });
+        self.timeline.extend(timeline);
+
+        self.final_time.start += rhs.final_time.start;
+        self.final_time.end += rhs.final_time.end;
+        self.estimated_time += rhs.estimated_time;
+
+        for stats in &rhs.frametime_stats {
+            match self
+                .frametime_stats
+                .binary_search_by_key(&stats.frametime, |f| f.frametime)
+            {
+                Ok(i) => self.frametime_stats[i].frame_count += &stats.frame_count,
+                Err(i) => self.frametime_stats.insert(i, stats.clone()),
+            }
+        }
+
+        self.save_count += &rhs.save_count;
+        self.shared_seed_set_count += &rhs.shared_seed_set_count;
+        self.button_set_count += &rhs.button_set_count;
+        self.lgagst_min_speed_set_count += &rhs.lgagst_min_speed_set_count;
+        self.reset_count += &rhs.reset_count;
+        self.comment_count += &rhs.comment_count;
+        self.change_angle_count += &rhs.change_angle_count;
+        self.target_yaw_override_count += &rhs.target_yaw_override_count;
+        self.auto_action_stats += &rhs.auto_action_stats;
+        self.strafe_stats += &rhs.strafe_stats;
+        self.key_stats += &rhs.key_stats;
+
+        self.mergeable_framebulks
+            .extend(rhs.mergeable_framebulks.iter().cloned());
+
+        for stats in &rhs.console_command_stats {
+            match self
+                .console_command_stats
+                .iter_mut()
+                .find(|s| s.name == stats.name)
+            {
+                Some(s) => {
+                    s.count += &stats.count;
+                    s.line_indices.extend(&stats.line_indices);
+                }
+                None => self.console_command_stats.push(stats.clone()),
+            }
+        }
+    }
+}
+
+impl<'a> Sum<&'a AnalyzerResult> for AnalyzerResult {
+    fn sum<I: Iterator<Item = &'a AnalyzerResult>>(iter: I) -> Self {
+        iter.fold(AnalyzerResult::default(), |mut total, result| {
+            total += result;
+            total
+        })
+    }
 }
 
 impl Display for AnalyzerResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_text(f, true)
+    }
+}
+
+impl AnalyzerResult {
+    /// Formats the analysis as text, with or without colours.
+    /// - Use [`Text`](crate::report::Text) to format without colours outside of the crate.
+    pub(crate) fn fmt_text(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        colour: bool,
+    ) -> std::fmt::Result {
+        let paint = |c: Colour, s| paint_if(c, s, colour);
         let minutes = |seconds: &Decimal| (seconds / dec!(60.0)).floor();
         let sub_seconds = |seconds: &Decimal| (seconds % dec!(60.0)).round_dp(3);
 
@@ -247,14 +880,14 @@ impl Display for AnalyzerResult {
         writeln!(
             f,
             "{}: {} ~ {}",
-            Red.paint("Final time"),
+            paint(Red, "Final time"),
             final_time_string.0,
             final_time_string.1
         )?;
         writeln!(
             f,
             "{}: {}",
-            Blue.paint("Estimated time"),
+            paint(Blue, "Estimated time"),
             estimated_time_string
         )?;
 
@@ -263,75 +896,112 @@ impl Display for AnalyzerResult {
         writeln!(
             f,
             "{}: {}s ~ {}s",
-            RGB(0xFF, 0x5F, 0x1F).paint("Final time secs"),
+            paint(RGB(0xFF, 0x5F, 0x1F), "Final time secs"),
             self.final_time.start,
             self.final_time.end
         )?;
         writeln!(
             f,
             "{}: {}s",
-            RGB(0x29, 0xB6, 0xF6).paint("Estimated secs"),
+            paint(RGB(0x29, 0xB6, 0xF6), "Estimated secs"),
             self.estimated_time
         )?;
         writeln!(f)?;
-        writeln!(f, "{}", Green.paint("Frametime stats"))?;
+        writeln!(f, "{}", paint(Green, "Frametime stats"))?;
         for stats in &self.frametime_stats {
             writeln!(f, "    {stats}")?;
         }
         writeln!(f)?;
-        writeln!(f, "{}: {}", Fixed(93).paint("Save count"), self.save_count)?;
+        writeln!(f, "{}: {}", paint(Fixed(93), "Save count"), self.save_count)?;
         writeln!(
             f,
             "{}: {}",
-            Fixed(99).paint("Shared seed set count"),
+            paint(Fixed(99), "Shared seed set count"),
             self.shared_seed_set_count
         )?;
         writeln!(
             f,
             "{}: {}",
-            Fixed(105).paint("Button set count"),
+            paint(Fixed(105), "Button set count"),
             self.button_set_count
         )?;
         writeln!(
             f,
             "{}: {}",
-            Fixed(111).paint("LGAGST min speed set count"),
+            paint(Fixed(111), "LGAGST min speed set count"),
             self.lgagst_min_speed_set_count
         )?;
         writeln!(
             f,
             "{}: {}",
-            Fixed(117).paint("Reset count"),
+            paint(Fixed(117), "Reset count"),
             self.reset_count
         )?;
         writeln!(
             f,
             "{}: {}",
-            Fixed(123).paint("Comment count"),
+            paint(Fixed(123), "Comment count"),
             self.comment_count
         )?;
         writeln!(
             f,
             "{}: {}",
-            Fixed(129).paint("Change angle count"),
+            paint(Fixed(129), "Change angle count"),
             self.change_angle_count
         )?;
         writeln!(
             f,
             "{}: {}",
-            Fixed(135).paint("Target yaw override count"),
+            paint(Fixed(135), "Target yaw override count"),
             self.target_yaw_override_count
-        )
+        )?;
+        writeln!(
+            f,
+            "{}: {} ({} framebulks)",
+            paint(Fixed(141), "Mergeable framebulk runs"),
+            self.mergeable_framebulks.len(),
+            self.mergeable_framebulks
+                .iter()
+                .map(|run| run.len())
+                .sum::<usize>()
+        )?;
+        writeln!(f)?;
+        writeln!(f, "{}", paint(Yellow, "Auto action stats"))?;
+        write!(f, "{}", self.auto_action_stats)?;
+        writeln!(f)?;
+        writeln!(f, "{}", paint(Cyan, "Strafe stats"))?;
+        write!(f, "{}", self.strafe_stats)?;
+        writeln!(f)?;
+        writeln!(f, "{}", paint(Purple, "Key stats"))?;
+        write!(f, "{}", self.key_stats)?;
+        writeln!(f)?;
+        writeln!(f, "{}", paint(RGB(0xFF, 0xA7, 0x26), "Console commands"))?;
+        for stats in &self.console_command_stats {
+            writeln!(f, "    {stats}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Paints the text with the colour, or leaves it plain if `colour` is `false`.
+pub(crate) fn paint_if(c: Colour, s: &str, colour: bool) -> ANSIString<'_> {
+    if colour {
+        c.paint(s)
+    } else {
+        Style::new().paint(s)
     }
 }
 
 /// The frametime stats of a HLTAS.
 /// Contains `frametime` and total `frame_count`.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FrametimeStats {
     /// The frametime, in milliseconds.
     pub frametime: Decimal,
     /// The total number of frames.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_biguint"))]
     pub frame_count: BigUint,
 }
 
@@ -343,11 +1013,488 @@ impl Display for FrametimeStats {
 
 /// The final time of a HLTAS.
 /// - `start` is the minimum final time, assuming all 0ms ducktap framebulks are 0ms.
+///   With limited 0ms ducktaps, only as many frames as the ducktaps left can be 0ms.
 /// - `end` is the maximum final time, assuming all 0ms ducktap framebulks aren't 0ms.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FinalTime {
     /// The minimum final time.
     pub start: Decimal,
     /// The maximum final time.
     pub end: Decimal,
 }
+
+/// A timeline entry of a framebulk in a HLTAS.
+/// - The frames and times are cumulative from the start of the HLTAS.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FrameBulkTimeline {
+    /// The index of the framebulk in the HLTAS lines.
+    pub line_index: usize,
+    /// The frame the framebulk starts on.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_biguint"))]
+    pub start_frame: BigUint,
+    /// The frame the framebulk ends on, exclusive.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_biguint"))]
+    pub end_frame: BigUint,
+    /// The final time at the end of the framebulk.
+    pub final_time: FinalTime,
+    /// The estimated time at the end of the framebulk.
+    pub estimated_time: Decimal,
+    /// If the framebulk can 0ms ducktap, including limited 0ms ducktaps carried over from an earlier framebulk.
+    pub zero_ms_ducktap: bool,
+}
+
+/// The usage of an action in a HLTAS.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ActionUsage {
+    /// The number of framebulks using the action.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_biguint"))]
+    pub framebulk_count: BigUint,
+    /// The total number of frames in the framebulks using the action.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_biguint"))]
+    pub frame_count: BigUint,
+}
+
+impl ActionUsage {
+    fn add_framebulk(&mut self, frame_count: &BigUint) {
+        self.framebulk_count += BigUint::one();
+        self.frame_count += frame_count;
+    }
+}
+
+impl AddAssign<&ActionUsage> for ActionUsage {
+    fn add_assign(&mut self, rhs: &ActionUsage) {
+        self.framebulk_count += &rhs.framebulk_count;
+        self.frame_count += &rhs.frame_count;
+    }
+}
+
+impl Display for ActionUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} framebulks, {} frames",
+            self.framebulk_count, self.frame_count
+        )
+    }
+}
+
+/// The usage of automatic actions in a HLTAS, taken from the framebulks `auto_actions`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AutoActionStats {
+    /// Leaving the ground by jumping.
+    pub jump: ActionUsage,
+    /// Leaving the ground by ducktapping, without 0ms.
+    pub ducktap: ActionUsage,
+    /// Leaving the ground by 0ms ducktapping.
+    pub zero_ms_ducktap: ActionUsage,
+    /// Leaving the ground at any speed.
+    pub speed_any: ActionUsage,
+    /// Leaving the ground at optimal speed.
+    pub speed_optimal: ActionUsage,
+    /// Leaving the ground at optimal speed, not taking maxspeed reduction into account.
+    pub speed_optimal_with_full_maxspeed: ActionUsage,
+    /// Leaving the ground a limited number of times.
+    pub times_limited: ActionUsage,
+    /// Leaving the ground any number of times within the framebulk.
+    pub times_unlimited: ActionUsage,
+    /// Automatic jumpbug.
+    pub jump_bug: ActionUsage,
+    /// Duck before collision.
+    pub duck_before_collision: ActionUsage,
+    /// Duck before ground.
+    pub duck_before_ground: ActionUsage,
+    /// Duck when jump.
+    pub duck_when_jump: ActionUsage,
+}
+
+impl AutoActionStats {
+    /// The stats with their display names, in display order.
+    pub fn entries(&self) -> [(&'static str, &ActionUsage); 12] {
+        [
+            ("Jump", &self.jump),
+            ("Ducktap", &self.ducktap),
+            ("0ms ducktap", &self.zero_ms_ducktap),
+            ("Any speed", &self.speed_any),
+            ("Optimal speed", &self.speed_optimal),
+            (
+                "Optimal speed with full maxspeed",
+                &self.speed_optimal_with_full_maxspeed,
+            ),
+            ("Limited times", &self.times_limited),
+            ("Unlimited times", &self.times_unlimited),
+            ("Jumpbug", &self.jump_bug),
+            ("Duck before collision", &self.duck_before_collision),
+            ("Duck before ground", &self.duck_before_ground),
+            ("Duck when jump", &self.duck_when_jump),
+        ]
+    }
+
+    fn add_framebulk(&mut self, auto_actions: &AutoActions, frame_count: &BigUint) {
+        if let Some(action) = &auto_actions.leave_ground_action {
+            match action.type_ {
+                LeaveGroundActionType::Jump => self.jump.add_framebulk(frame_count),
+                LeaveGroundActionType::DuckTap { zero_ms: false } => {
+                    self.ducktap.add_framebulk(frame_count)
+                }
+                LeaveGroundActionType::DuckTap { zero_ms: true } => {
+                    self.zero_ms_ducktap.add_framebulk(frame_count)
+                }
+            }
+
+            match action.speed {
+                LeaveGroundActionSpeed::Any => self.speed_any.add_framebulk(frame_count),
+                LeaveGroundActionSpeed::Optimal => self.speed_optimal.add_framebulk(frame_count),
+                LeaveGroundActionSpeed::OptimalWithFullMaxspeed => self
+                    .speed_optimal_with_full_maxspeed
+                    .add_framebulk(frame_count),
+            }
+
+            match action.times {
+                Times::Limited(_) => self.times_limited.add_framebulk(frame_count),
+                Times::UnlimitedWithinFrameBulk => self.times_unlimited.add_framebulk(frame_count),
+            }
+        }
+
+        if auto_actions.jump_bug.is_some() {
+            self.jump_bug.add_framebulk(frame_count);
+        }
+        if auto_actions.duck_before_collision.is_some() {
+            self.duck_before_collision.add_framebulk(frame_count);
+        }
+        if auto_actions.duck_before_ground.is_some() {
+            self.duck_before_ground.add_framebulk(frame_count);
+        }
+        if auto_actions.duck_when_jump.is_some() {
+            self.duck_when_jump.add_framebulk(frame_count);
+        }
+    }
+}
+
+impl AddAssign<&AutoActionStats> for AutoActionStats {
+    fn add_assign(&mut self, rhs: &AutoActionStats) {
+        self.jump += &rhs.jump;
+        self.ducktap += &rhs.ducktap;
+        self.zero_ms_ducktap += &rhs.zero_ms_ducktap;
+        self.speed_any += &rhs.speed_any;
+        self.speed_optimal += &rhs.speed_optimal;
+        self.speed_optimal_with_full_maxspeed += &rhs.speed_optimal_with_full_maxspeed;
+        self.times_limited += &rhs.times_limited;
+        self.times_unlimited += &rhs.times_unlimited;
+        self.jump_bug += &rhs.jump_bug;
+        self.duck_before_collision += &rhs.duck_before_collision;
+        self.duck_before_ground += &rhs.duck_before_ground;
+        self.duck_when_jump += &rhs.duck_when_jump;
+    }
+}
+
+impl Display for AutoActionStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, usage) in self.entries() {
+            writeln!(f, "    {name}: {usage}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The usage of automatic strafing in a HLTAS, taken from the framebulks `auto_actions.movement`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StrafeStats {
+    /// No automatic strafing, either with no automatic movement or with only the yaw set.
+    pub no_strafe: ActionUsage,
+    /// Strafing with maximum acceleration.
+    pub max_accel: ActionUsage,
+    /// Strafing with maximum angle.
+    pub max_angle: ActionUsage,
+    /// Strafing with maximum decceleration.
+    pub max_deccel: ActionUsage,
+    /// Strafing with constant speed.
+    pub const_speed: ActionUsage,
+    /// Strafing with a constant yawspeed.
+    pub const_yawspeed: ActionUsage,
+    /// Strafing with maximum acceleration and an additional yaw offset.
+    pub max_accel_yaw_offset: ActionUsage,
+    /// Strafing to the left.
+    pub dir_left: ActionUsage,
+    /// Strafing to the right.
+    pub dir_right: ActionUsage,
+    /// Strafing in the best direction.
+    pub dir_best: ActionUsage,
+    /// Strafing towards a yaw angle.
+    pub dir_yaw: ActionUsage,
+    /// Strafing towards a point.
+    pub dir_point: ActionUsage,
+    /// Strafing along a line.
+    pub dir_line: ActionUsage,
+    /// Strafing left then right, alternating every few frames.
+    pub dir_left_right: ActionUsage,
+    /// Strafing right then left, alternating every few frames.
+    pub dir_right_left: ActionUsage,
+}
+
+impl StrafeStats {
+    /// The stats with their display names, in display order.
+    pub fn entries(&self) -> [(&'static str, &ActionUsage); 15] {
+        [
+            ("No strafe", &self.no_strafe),
+            ("Max accel", &self.max_accel),
+            ("Max angle", &self.max_angle),
+            ("Max deccel", &self.max_deccel),
+            ("Const speed", &self.const_speed),
+            ("Const yawspeed", &self.const_yawspeed),
+            ("Max accel yaw offset", &self.max_accel_yaw_offset),
+            ("Left", &self.dir_left),
+            ("Right", &self.dir_right),
+            ("Best", &self.dir_best),
+            ("Yaw", &self.dir_yaw),
+            ("Point", &self.dir_point),
+            ("Line", &self.dir_line),
+            ("Left-right", &self.dir_left_right),
+            ("Right-left", &self.dir_right_left),
+        ]
+    }
+
+    fn add_framebulk(&mut self, auto_actions: &AutoActions, frame_count: &BigUint) {
+        let settings = match &auto_actions.movement {
+            Some(AutoMovement::Strafe(settings)) => settings,
+            Some(AutoMovement::SetYaw(_)) | None => {
+                self.no_strafe.add_framebulk(frame_count);
+                return;
+            }
+        };
+
+        match settings.type_ {
+            StrafeType::MaxAccel => self.max_accel.add_framebulk(frame_count),
+            StrafeType::MaxAngle => self.max_angle.add_framebulk(frame_count),
+            StrafeType::MaxDeccel => self.max_deccel.add_framebulk(frame_count),
+            StrafeType::ConstSpeed => self.const_speed.add_framebulk(frame_count),
+            StrafeType::ConstYawspeed(_) => self.const_yawspeed.add_framebulk(frame_count),
+            StrafeType::MaxAccelYawOffset { .. } => {
+                self.max_accel_yaw_offset.add_framebulk(frame_count)
+            }
+        }
+
+        match settings.dir {
+            StrafeDir::Left => self.dir_left.add_framebulk(frame_count),
+            StrafeDir::Right => self.dir_right.add_framebulk(frame_count),
+            StrafeDir::Best => self.dir_best.add_framebulk(frame_count),
+            StrafeDir::Yaw(_) => self.dir_yaw.add_framebulk(frame_count),
+            StrafeDir::Point { .. } => self.dir_point.add_framebulk(frame_count),
+            StrafeDir::Line { .. } => self.dir_line.add_framebulk(frame_count),
+            StrafeDir::LeftRight(_) => self.dir_left_right.add_framebulk(frame_count),
+            StrafeDir::RightLeft(_) => self.dir_right_left.add_framebulk(frame_count),
+        }
+    }
+}
+
+impl AddAssign<&StrafeStats> for StrafeStats {
+    fn add_assign(&mut self, rhs: &StrafeStats) {
+        self.no_strafe += &rhs.no_strafe;
+        self.max_accel += &rhs.max_accel;
+        self.max_angle += &rhs.max_angle;
+        self.max_deccel += &rhs.max_deccel;
+        self.const_speed += &rhs.const_speed;
+        self.const_yawspeed += &rhs.const_yawspeed;
+        self.max_accel_yaw_offset += &rhs.max_accel_yaw_offset;
+        self.dir_left += &rhs.dir_left;
+        self.dir_right += &rhs.dir_right;
+        self.dir_best += &rhs.dir_best;
+        self.dir_yaw += &rhs.dir_yaw;
+        self.dir_point += &rhs.dir_point;
+        self.dir_line += &rhs.dir_line;
+        self.dir_left_right += &rhs.dir_left_right;
+        self.dir_right_left += &rhs.dir_right_left;
+    }
+}
+
+impl Display for StrafeStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, usage) in self.entries() {
+            writeln!(f, "    {name}: {usage}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The usage of a key in a HLTAS.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct KeyUsage {
+    /// The number of frames the key is held for.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_biguint"))]
+    pub frame_count: BigUint,
+    /// The number of separate presses of the key.
+    /// - A press is a framebulk holding the key, after a framebulk that didn't.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_biguint"))]
+    pub press_count: BigUint,
+}
+
+impl Display for KeyUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} frames, {} presses",
+            self.frame_count, self.press_count
+        )
+    }
+}
+
+impl AddAssign<&KeyUsage> for KeyUsage {
+    fn add_assign(&mut self, rhs: &KeyUsage) {
+        self.frame_count += &rhs.frame_count;
+        self.press_count += &rhs.press_count;
+    }
+}
+
+/// The usage of movement and action keys in a HLTAS, taken from the framebulks `movement_keys` and `action_keys`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct KeyStats {
+    /// `+forward`
+    pub forward: KeyUsage,
+    /// `+moveleft`
+    pub left: KeyUsage,
+    /// `+moveright`
+    pub right: KeyUsage,
+    /// `+back`
+    pub back: KeyUsage,
+    /// `+moveup`
+    pub up: KeyUsage,
+    /// `+movedown`
+    pub down: KeyUsage,
+    /// `+jump`
+    pub jump: KeyUsage,
+    /// `+duck`
+    pub duck: KeyUsage,
+    /// `+use`
+    pub use_: KeyUsage,
+    /// `+attack1`
+    pub attack_1: KeyUsage,
+    /// `+attack2`
+    pub attack_2: KeyUsage,
+    /// `+reload`
+    pub reload: KeyUsage,
+}
+
+impl KeyStats {
+    /// The stats with their display names, in display order.
+    pub fn entries(&self) -> [(&'static str, &KeyUsage); 12] {
+        [
+            ("Forward", &self.forward),
+            ("Left", &self.left),
+            ("Right", &self.right),
+            ("Back", &self.back),
+            ("Up", &self.up),
+            ("Down", &self.down),
+            ("Jump", &self.jump),
+            ("Duck", &self.duck),
+            ("Use", &self.use_),
+            ("Attack1", &self.attack_1),
+            ("Attack2", &self.attack_2),
+            ("Reload", &self.reload),
+        ]
+    }
+
+    fn add_framebulk(
+        &mut self,
+        (movement, action): &(MovementKeys, ActionKeys),
+        (previous_movement, previous_action): &(MovementKeys, ActionKeys),
+        frame_count: &BigUint,
+    ) {
+        let keys = [
+            (
+                &mut self.forward,
+                movement.forward,
+                previous_movement.forward,
+            ),
+            (&mut self.left, movement.left, previous_movement.left),
+            (&mut self.right, movement.right, previous_movement.right),
+            (&mut self.back, movement.back, previous_movement.back),
+            (&mut self.up, movement.up, previous_movement.up),
+            (&mut self.down, movement.down, previous_movement.down),
+            (&mut self.jump, action.jump, previous_action.jump),
+            (&mut self.duck, action.duck, previous_action.duck),
+            (&mut self.use_, action.use_, previous_action.use_),
+            (
+                &mut self.attack_1,
+                action.attack_1,
+                previous_action.attack_1,
+            ),
+            (
+                &mut self.attack_2,
+                action.attack_2,
+                previous_action.attack_2,
+            ),
+            (&mut self.reload, action.reload, previous_action.reload),
+        ];
+
+        for (usage, held, previously_held) in keys {
+            if !held {
+                continue;
+            }
+
+            usage.frame_count += frame_count;
+            if !previously_held {
+                usage.press_count += BigUint::one();
+            }
+        }
+    }
+}
+
+impl AddAssign<&KeyStats> for KeyStats {
+    fn add_assign(&mut self, rhs: &KeyStats) {
+        self.forward += &rhs.forward;
+        self.left += &rhs.left;
+        self.right += &rhs.right;
+        self.back += &rhs.back;
+        self.up += &rhs.up;
+        self.down += &rhs.down;
+        self.jump += &rhs.jump;
+        self.duck += &rhs.duck;
+        self.use_ += &rhs.use_;
+        self.attack_1 += &rhs.attack_1;
+        self.attack_2 += &rhs.attack_2;
+        self.reload += &rhs.reload;
+    }
+}
+
+impl Display for KeyStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, usage) in self.entries() {
+            writeln!(f, "    {name}: {usage}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The usage of a console command in a HLTAS.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConsoleCommandStats {
+    /// The name of the command, for example `changelevel` or `+attack`.
+    pub name: String,
+    /// The number of times the command is used.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_biguint"))]
+    pub count: BigUint,
+    /// The line indices of the framebulks using the command, in order.
+    pub line_indices: Vec<usize>,
+}
+
+impl Display for ConsoleCommandStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} times", self.name, self.count)?;
+
+        if let Some(first) = self.line_indices.first() {
+            write!(f, ", first at line index {first}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+//! Reads the JSON TAS log that Bunnymod XT writes with `bxt_tas_write_log`.
+//! - Requires the `serde` feature.
+//!
+//! ```
+//! # use hltas_framebulk_analyzer::bxt_log::parse_bxt_log;
+//! let log = parse_bxt_log(
+//!     r#"{"tool_ver": "1.0", "game_mod": "valve", "phys_frames": [
+//!         {"ft": 0.004, "cmdframes": [{"ms": 4, "postpm": {"pos": [0, 0, 36], "vel": [320, 0, 0], "og": true}}]},
+//!         {"ft": 0.004, "cmdframes": [{"ms": 4, "postpm": {"pos": [1.28, 0, 36], "vel": [320, 0, 0], "og": true}}]}
+//!     ]}"#,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(log.physics_frames.len(), 2);
+//! assert_eq!(log.analysis().final_time.start.to_string(), "0.008");
+//! ```
+
+use std::{collections::BTreeMap, io::Read};
+
+use num_bigint::BigUint;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::analyzer::{AnalyzerResult, FinalTime, FrametimeStats};
+
+/// Function that parses a Bunnymod XT TAS log from a JSON string.
+/// - Fields that aren't used by the analyzer are ignored, and missing fields are left at their default.
+pub fn parse_bxt_log(json: &str) -> Result<BxtLog, BxtLogError> {
+    Ok(serde_json::from_str(json)?)
+}
+
+/// Function that reads a Bunnymod XT TAS log from a reader, such as a log file.
+/// - Same as [`parse_bxt_log`][parse_bxt_log] other than reading the JSON.
+pub fn read_bxt_log<R: Read>(reader: R) -> Result<BxtLog, BxtLogError> {
+    Ok(serde_json::from_reader(reader)?)
+}
+
+/// Error type for parsing a Bunnymod XT TAS log.
+#[derive(Debug, Error)]
+pub enum BxtLogError {
+    /// Error when the log isn't valid JSON, or isn't in the TAS log layout.
+    #[error("Failed to parse the bxt log: {0}")]
+    JsonError(#[from] serde_json::Error),
+}
+
+/// A Bunnymod XT TAS log.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BxtLog {
+    /// The version of Bunnymod XT that wrote the log.
+    #[serde(rename = "tool_ver", default)]
+    pub tool_version: Option<String>,
+    /// The game directory, such as `valve`.
+    #[serde(default)]
+    pub game_mod: Option<String>,
+    /// Every physics frame the game ran while logging, in order.
+    #[serde(rename = "phys_frames", default)]
+    pub physics_frames: Vec<PhysicsFrame>,
+}
+
+impl BxtLog {
+    /// The real time of the log, which is the sum of the frametimes of all physics frames that aren't paused.
+    pub fn real_time(&self) -> Decimal {
+        self.physics_frames
+            .iter()
+            .filter(|frame| !frame.paused)
+            .map(|frame| frame.frametime)
+            .sum()
+    }
+
+    /// Summarizes the log as an [`AnalyzerResult`][AnalyzerResult], so it can be shown and compared like the analysis of a HLTAS.
+    /// - The final time and estimated time are both the [`real_time`][BxtLog::real_time].
+    /// - The frametime stats are from the frametimes of the physics frames that aren't paused.
+    /// - Everything that only a HLTAS has, such as the line counts and the timeline, is left empty.
+    pub fn analysis(&self) -> AnalyzerResult {
+        let real_time = self.real_time();
+
+        let mut frametime_stats = BTreeMap::<Decimal, BigUint>::new();
+        for frame in self.physics_frames.iter().filter(|frame| !frame.paused) {
+            *frametime_stats.entry(frame.frametime).or_default() += 1u32;
+        }
+
+        AnalyzerResult {
+            final_time: FinalTime {
+                start: real_time,
+                end: real_time,
+            },
+            estimated_time: real_time,
+            frametime_stats: frametime_stats
+                .into_iter()
+                .map(|(frametime, frame_count)| FrametimeStats {
+                    frametime,
+                    frame_count,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A physics frame of a Bunnymod XT TAS log.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhysicsFrame {
+    /// The frametime of the physics frame, in seconds.
+    #[serde(rename = "ft", default)]
+    pub frametime: Decimal,
+    /// If the game was paused, such as while loading a map.
+    #[serde(rename = "pause", default)]
+    pub paused: bool,
+    /// The console commands that ran in the physics frame.
+    #[serde(rename = "cbuf", default)]
+    pub command_buffer: Option<String>,
+    /// The player commands the physics frame ran, usually one, or none while paused.
+    #[serde(rename = "cmdframes", default)]
+    pub command_frames: Vec<CommandFrame>,
+}
+
+impl PhysicsFrame {
+    /// The player state after the last command frame of the physics frame.
+    pub fn player_state(&self) -> Option<&PlayerState> {
+        self.command_frames
+            .iter()
+            .rev()
+            .find_map(|frame| frame.post_player_move.as_ref())
+    }
+
+    /// The player position at the end of the physics frame.
+    pub fn position(&self) -> Option<[f32; 3]> {
+        self.player_state().map(|state| state.position)
+    }
+
+    /// The player velocity at the end of the physics frame.
+    pub fn velocity(&self) -> Option<[f32; 3]> {
+        self.player_state().map(|state| state.velocity)
+    }
+
+    /// If the player is on the ground at the end of the physics frame.
+    pub fn on_ground(&self) -> Option<bool> {
+        self.player_state().map(|state| state.on_ground)
+    }
+}
+
+/// A player command of a physics frame in a Bunnymod XT TAS log.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommandFrame {
+    /// The duration of the command, in milliseconds.
+    /// - This is 0 for the 0ms frames of 0ms ducktaps.
+    #[serde(rename = "ms", default)]
+    pub msec: u32,
+    /// The buttons held in the command, as the game's `IN_*` bit flags.
+    #[serde(rename = "btns", default)]
+    pub buttons: u32,
+    /// The player state before the player movement of the command.
+    #[serde(rename = "prepm", default)]
+    pub pre_player_move: Option<PlayerState>,
+    /// The player state after the player movement of the command.
+    #[serde(rename = "postpm", default)]
+    pub post_player_move: Option<PlayerState>,
+}
+
+/// The player state of a command frame in a Bunnymod XT TAS log.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerState {
+    /// The player position.
+    #[serde(rename = "pos", default)]
+    pub position: [f32; 3],
+    /// The player velocity.
+    #[serde(rename = "vel", default)]
+    pub velocity: [f32; 3],
+    /// If the player is on the ground.
+    #[serde(rename = "og", default)]
+    pub on_ground: bool,
+}
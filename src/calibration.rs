@@ -0,0 +1,214 @@
+//! Calibrates the ducktap airtime of the 0ms estimate for each map, from Bunnymod XT TAS logs.
+//!
+//! The calibration is saved as a small config file, with a `map = airtime` line for each map:
+//! ```
+//! # use hltas_framebulk_analyzer::calibration::Calibration;
+//! let calibration = "# ducktap airtime in seconds\nc1a0 = 0.2\nc1a0a = 0.196\n"
+//!     .parse::<Calibration>()
+//!     .unwrap();
+//!
+//! assert_eq!(calibration.analyzer_options("c1a0a").ducktap_airtime().to_string(), "0.196");
+//! assert_eq!(calibration.analyzer_options("c1a1").ducktap_airtime().normalize().to_string(), "0.201");
+//! ```
+
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use hltas::HLTAS;
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::analyzer::{map_change, AnalyzerOptions};
+#[cfg(feature = "serde")]
+use crate::bxt_log::BxtLog;
+
+/// Calibrated ducktap airtimes for each map.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Calibration {
+    /// The ducktap airtime of each map, in seconds.
+    pub ducktap_airtimes: BTreeMap<String, Decimal>,
+}
+
+impl Calibration {
+    /// Calibrates from bxt logs, each with the map the log starts on if it's known.
+    /// - The airtime of a map is the average of every ducktap airtime measured on that map, rounded to 6 decimal places.
+    /// - See [`measure_ducktap_airtimes`][measure_ducktap_airtimes] for how the airtimes are measured.
+    /// - Requires the `serde` feature.
+    #[cfg(feature = "serde")]
+    pub fn from_logs<'a>(logs: impl IntoIterator<Item = (&'a BxtLog, Option<&'a str>)>) -> Self {
+        let mut samples = BTreeMap::<String, Vec<Decimal>>::new();
+
+        for (log, start_map) in logs {
+            for sample in measure_ducktap_airtimes(log, start_map) {
+                samples.entry(sample.map).or_default().push(sample.airtime);
+            }
+        }
+
+        let ducktap_airtimes = samples
+            .into_iter()
+            .map(|(map, airtimes)| {
+                let average = airtimes.iter().sum::<Decimal>() / Decimal::from(airtimes.len());
+                (map, average.round_dp(6))
+            })
+            .collect();
+
+        Self { ducktap_airtimes }
+    }
+
+    /// The analyzer options for a HLTAS starting on the map, using the calibrated ducktap airtime if the map has one.
+    /// - The airtime switches to the calibrated airtime of the next map on each map change.
+    pub fn analyzer_options(&self, map: &str) -> AnalyzerOptions {
+        AnalyzerOptions {
+            calibrated_ducktap_airtime: self.ducktap_airtimes.get(map).copied(),
+            map_ducktap_airtimes: self.ducktap_airtimes.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// The analyzer options for the HLTAS, starting on the map in its `load_command` property.
+    /// - The airtime switches to the calibrated airtime of the next map on each map change.
+    /// - Until the first map change, the default airtime is used if the HLTAS doesn't load a map, or the map isn't calibrated.
+    pub fn analyzer_options_for(&self, hltas: &HLTAS) -> AnalyzerOptions {
+        match hltas
+            .properties
+            .load_command
+            .as_deref()
+            .and_then(map_change)
+        {
+            Some(map) => self.analyzer_options(map),
+            None => AnalyzerOptions {
+                map_ducktap_airtimes: self.ducktap_airtimes.clone(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Writes the calibration as a config file, which can be parsed back with [`FromStr`](std::str::FromStr).
+impl Display for Calibration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# ducktap airtime in seconds")?;
+        for (map, airtime) in &self.ducktap_airtimes {
+            writeln!(f, "{map} = {airtime}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a calibration config file.
+/// - Empty lines and lines starting with `#` are ignored.
+impl FromStr for Calibration {
+    type Err = CalibrationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ducktap_airtimes = BTreeMap::new();
+
+        for (line_index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid_line = || CalibrationError::InvalidLine {
+                line: line.to_string(),
+                line_index,
+            };
+            let (map, airtime) = line.split_once('=').ok_or_else(invalid_line)?;
+            let map = map.trim();
+            if map.is_empty() {
+                return Err(invalid_line());
+            }
+            let airtime = airtime
+                .trim()
+                .parse::<Decimal>()
+                .map_err(|_| invalid_line())?;
+
+            ducktap_airtimes.insert(map.to_string(), airtime);
+        }
+
+        Ok(Self { ducktap_airtimes })
+    }
+}
+
+/// Error type for parsing a [`Calibration`][Calibration] config file.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum CalibrationError {
+    /// Error when a line isn't a `map = airtime` line.
+    #[error("Invalid calibration line {line} at line index {line_index}, expected map = airtime")]
+    InvalidLine {
+        line: String,
+        /// The index of the line in the config file.
+        line_index: usize,
+    },
+}
+
+/// A ducktap airtime measured from a bxt log.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DucktapSample {
+    /// The map the ducktap was done on.
+    pub map: String,
+    /// The index of the 0ms physics frame of the ducktap in the log.
+    pub frame_index: usize,
+    /// The time from the 0ms frame to landing, in seconds.
+    pub airtime: Decimal,
+}
+
+/// Function that measures the airtime of every 0ms ducktap in a bxt log.
+/// - A ducktap starts on a physics frame with a 0ms command frame that leaves the ground,
+///   and the airtime is the sum of the frametimes after it up to and including the frame the player lands on.
+/// - The map is tracked from the `map`, `changelevel` and `changelevel2` commands of the logged command buffer,
+///   starting with `start_map`, and ducktaps before the map is known are skipped.
+/// - Ducktaps that are interrupted by a pause, a map change or another 0ms frame are skipped.
+/// - Requires the `serde` feature.
+#[cfg(feature = "serde")]
+pub fn measure_ducktap_airtimes(log: &BxtLog, start_map: Option<&str>) -> Vec<DucktapSample> {
+    let mut samples = Vec::new();
+    let mut map = start_map.map(str::to_string);
+    // the frame index and airtime so far of the ducktap in the air
+    let mut ducktap: Option<(usize, Decimal)> = None;
+
+    for (frame_index, frame) in log.physics_frames.iter().enumerate() {
+        let next_map = frame
+            .command_buffer
+            .as_deref()
+            .and_then(|commands| commands.lines().rev().find_map(map_change));
+        if let Some(next_map) = next_map {
+            map = Some(next_map.to_string());
+            ducktap = None;
+        }
+
+        if frame.paused {
+            ducktap = None;
+            continue;
+        }
+
+        let zero_ms = frame.command_frames.iter().any(|cmd| cmd.msec == 0);
+        let on_ground = frame.on_ground();
+
+        if zero_ms {
+            ducktap = (on_ground == Some(false)).then_some((frame_index, Decimal::ZERO));
+            continue;
+        }
+
+        if let Some((start, airtime)) = &mut ducktap {
+            *airtime += frame.frametime;
+
+            match on_ground {
+                Some(true) => {
+                    if let Some(map) = &map {
+                        samples.push(DucktapSample {
+                            map: map.clone(),
+                            frame_index: *start,
+                            airtime: *airtime,
+                        });
+                    }
+                    ducktap = None;
+                }
+                Some(false) => (),
+                None => ducktap = None,
+            }
+        }
+    }
+
+    samples
+}
@@ -0,0 +1,366 @@
+//! Compares the analysis of two hltas files, usually two versions of the same route.
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use hltas::HLTAS;
+use num_bigint::{BigInt, BigUint};
+use rust_decimal::Decimal;
+
+use crate::{
+    analyzer::{analyze_hltas_with, AnalyzerOptions, AnalyzerResult, Error, FinalTime},
+    segments::{analyze_hltas_segments, SegmentMarker, SegmentOptions},
+};
+
+/// Function that compares the analysis of two HLTAS, returning the change from `old` to `new`.
+/// - Both are analyzed with the given [`AnalyzerOptions`][AnalyzerOptions].
+/// - Both are split into segments at the comment markers of the given [`SegmentOptions`][SegmentOptions],
+///   and the segments started by the same comment marker are compared.
+///   If a comment marker is used more than once, they are matched in order.
+/// - The other markers, such as `save` lines, don't split the segments.
+/// - Segments that aren't started by a comment marker, or only are in one of the HLTAS, are left out.
+/// - Only can fail if the frametime can't be parsed as a [`Decimal`](rust_decimal::Decimal).
+pub fn compare_hltas<'a>(
+    old: &'a HLTAS,
+    new: &'a HLTAS,
+    segment_options: &SegmentOptions,
+    analyzer_options: &AnalyzerOptions,
+) -> Result<Comparison, Error<'a>> {
+    let total = compare_analysis(
+        &analyze_hltas_with(old, analyzer_options)?,
+        &analyze_hltas_with(new, analyzer_options)?,
+    );
+
+    // only comment markers are matched, so other markers would cut the matched segments short
+    let segment_options = SegmentOptions {
+        split_on_save: false,
+        split_on_reset: false,
+        split_on_map_change: false,
+        ..segment_options.clone()
+    };
+
+    let old_segments = analyze_hltas_segments(old, &segment_options, analyzer_options)?;
+    let mut new_segments = analyze_hltas_segments(new, &segment_options, analyzer_options)?
+        .into_iter()
+        .map(Some)
+        .collect::<Vec<_>>();
+
+    let mut segments = Vec::new();
+    for old_segment in old_segments {
+        let Some(SegmentMarker::Comment(name)) = &old_segment.marker else {
+            continue;
+        };
+
+        // the first new segment with the same marker that isn't matched yet
+        let new_segment = new_segments.iter_mut().find(|segment| {
+            segment
+                .as_ref()
+                .is_some_and(|segment| segment.marker == old_segment.marker)
+        });
+        if let Some(new_segment) = new_segment.and_then(Option::take) {
+            segments.push(SegmentComparison {
+                name: name.clone(),
+                analysis: compare_analysis(&old_segment.analysis, &new_segment.analysis),
+            });
+        }
+    }
+
+    Ok(Comparison { total, segments })
+}
+
+/// Function that compares two analysis results, returning the change from `old` to `new`.
+pub fn compare_analysis(old: &AnalyzerResult, new: &AnalyzerResult) -> AnalysisComparison {
+    let mut frametime_stats = BTreeMap::<Decimal, Change<BigUint>>::new();
+    for stats in &old.frametime_stats {
+        frametime_stats
+            .entry(stats.frametime)
+            .or_default()
+            .old
+            .clone_from(&stats.frame_count);
+    }
+    for stats in &new.frametime_stats {
+        frametime_stats
+            .entry(stats.frametime)
+            .or_default()
+            .new
+            .clone_from(&stats.frame_count);
+    }
+
+    let old_counters = counters(old);
+    let mut counters = counters(new)
+        .into_iter()
+        .map(|(name, count)| CounterComparison {
+            name,
+            count: Change {
+                old: BigUint::default(),
+                new: count,
+            },
+        })
+        .collect::<Vec<_>>();
+    for (name, count) in old_counters {
+        match counters.iter_mut().find(|counter| counter.name == name) {
+            Some(counter) => counter.count.old = count,
+            // only console commands can be missing from one of the results
+            None => counters.push(CounterComparison {
+                name,
+                count: Change {
+                    old: count,
+                    new: BigUint::default(),
+                },
+            }),
+        }
+    }
+
+    AnalysisComparison {
+        final_time: Change {
+            old: old.final_time,
+            new: new.final_time,
+        },
+        estimated_time: Change {
+            old: old.estimated_time,
+            new: new.estimated_time,
+        },
+        frametime_stats: frametime_stats
+            .into_iter()
+            .map(|(frametime, frame_count)| FrametimeStatsComparison {
+                frametime,
+                frame_count,
+            })
+            .collect(),
+        counters,
+    }
+}
+
+/// Every counter of the analysis with its display name, in display order.
+fn counters(analysis: &AnalyzerResult) -> Vec<(String, BigUint)> {
+    let mut counters = vec![("Frame count".to_string(), analysis.frame_count())];
+
+    counters.extend(
+        analysis
+            .counts()
+            .into_iter()
+            .map(|(name, count)| (name.to_string(), count.clone())),
+    );
+    counters.push((
+        "Mergeable framebulk runs".to_string(),
+        analysis.mergeable_framebulks.len().into(),
+    ));
+
+    let action_usages = analysis
+        .auto_action_stats
+        .entries()
+        .into_iter()
+        .map(|(name, usage)| (format!("Auto action stats: {name}"), usage))
+        .chain(
+            analysis
+                .strafe_stats
+                .entries()
+                .into_iter()
+                .map(|(name, usage)| (format!("Strafe stats: {name}"), usage)),
+        );
+    for (name, usage) in action_usages {
+        counters.push((format!("{name} framebulks"), usage.framebulk_count.clone()));
+        counters.push((format!("{name} frames"), usage.frame_count.clone()));
+    }
+
+    for (name, usage) in analysis.key_stats.entries() {
+        counters.push((
+            format!("Key stats: {name} frames"),
+            usage.frame_count.clone(),
+        ));
+        counters.push((
+            format!("Key stats: {name} presses"),
+            usage.press_count.clone(),
+        ));
+    }
+
+    for stats in &analysis.console_command_stats {
+        counters.push((
+            format!("Console commands: {} count", stats.name),
+            stats.count.clone(),
+        ));
+    }
+
+    counters
+}
+
+/// A value before and after a change.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Change<T> {
+    /// The value in the old HLTAS.
+    pub old: T,
+    /// The value in the new HLTAS.
+    pub new: T,
+}
+
+impl Change<Decimal> {
+    /// How much the value went up, being negative if it went down.
+    pub fn difference(&self) -> Decimal {
+        self.new - self.old
+    }
+}
+
+impl Change<FinalTime> {
+    /// How much the start and end of the final time went up, being negative if they went down.
+    pub fn difference(&self) -> FinalTime {
+        FinalTime {
+            start: self.new.start - self.old.start,
+            end: self.new.end - self.old.end,
+        }
+    }
+}
+
+impl Change<BigUint> {
+    /// How much the count went up, being negative if it went down.
+    pub fn difference(&self) -> BigInt {
+        BigInt::from(self.new.clone()) - BigInt::from(self.old.clone())
+    }
+}
+
+/// The comparison of two HLTAS, from [`compare_hltas`][compare_hltas].
+/// - The [`Display`](std::fmt::Display) output shows the changed times and counters,
+///   and how much time each matched segment saves or loses.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Comparison {
+    /// The comparison of the whole HLTAS.
+    pub total: AnalysisComparison,
+    /// The comparison of the segments matched by comment markers, in the order of the old HLTAS.
+    pub segments: Vec<SegmentComparison>,
+}
+
+/// The comparison of two analysis results, from [`compare_analysis`][compare_analysis].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AnalysisComparison {
+    /// The final time.
+    pub final_time: Change<FinalTime>,
+    /// The estimated time.
+    pub estimated_time: Change<Decimal>,
+    /// The frame count of every frametime in either analysis, sorted by frametime.
+    /// - A frametime only in one of them has a frame count of 0 in the other.
+    pub frametime_stats: Vec<FrametimeStatsComparison>,
+    /// Every counter of the analysis, such as the frame count, the special line counts,
+    /// and the auto action, strafe, key and console command usages.
+    pub counters: Vec<CounterComparison>,
+}
+
+/// The frame count of a frametime in two analysis results.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FrametimeStatsComparison {
+    /// The frametime.
+    pub frametime: Decimal,
+    /// The total number of frames with the frametime.
+    pub frame_count: Change<BigUint>,
+}
+
+/// A counter in two analysis results.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CounterComparison {
+    /// The display name of the counter, such as `Save count`.
+    pub name: String,
+    /// The count.
+    pub count: Change<BigUint>,
+}
+
+/// The comparison of a segment started by the same comment marker in both HLTAS.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SegmentComparison {
+    /// The name of the segment, from the comment marker.
+    pub name: String,
+    /// The comparison of the segment analysis.
+    pub analysis: AnalysisComparison,
+}
+
+/// Describes the change of the estimated time, such as `saves 0.132s`.
+fn time_change(change: &Change<Decimal>) -> String {
+    let difference = change.difference().normalize();
+
+    if difference.is_zero() {
+        "no change".to_string()
+    } else if difference.is_sign_negative() {
+        format!("saves {}s", difference.abs())
+    } else {
+        format!("loses {difference}s")
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let total = &self.total;
+
+        writeln!(
+            f,
+            "Final time: {}s ~ {}s -> {}s ~ {}s ({:+}s ~ {:+}s)",
+            total.final_time.old.start.normalize(),
+            total.final_time.old.end.normalize(),
+            total.final_time.new.start.normalize(),
+            total.final_time.new.end.normalize(),
+            total.final_time.difference().start.normalize(),
+            total.final_time.difference().end.normalize(),
+        )?;
+        writeln!(
+            f,
+            "Estimated time: {}s -> {}s ({})",
+            total.estimated_time.old.normalize(),
+            total.estimated_time.new.normalize(),
+            time_change(&total.estimated_time),
+        )?;
+
+        let frametime_stats = total
+            .frametime_stats
+            .iter()
+            .filter(|stats| stats.frame_count.old != stats.frame_count.new)
+            .collect::<Vec<_>>();
+        if !frametime_stats.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Frametime stats")?;
+            for stats in frametime_stats {
+                writeln!(
+                    f,
+                    "    {}s: {} -> {} frames ({:+})",
+                    stats.frametime,
+                    stats.frame_count.old,
+                    stats.frame_count.new,
+                    stats.frame_count.difference()
+                )?;
+            }
+        }
+
+        let counters = total
+            .counters
+            .iter()
+            .filter(|counter| counter.count.old != counter.count.new)
+            .collect::<Vec<_>>();
+        if !counters.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Counters")?;
+            for counter in counters {
+                writeln!(
+                    f,
+                    "    {}: {} -> {} ({:+})",
+                    counter.name,
+                    counter.count.old,
+                    counter.count.new,
+                    counter.count.difference()
+                )?;
+            }
+        }
+
+        if !self.segments.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Segments")?;
+            for segment in &self.segments {
+                let estimated_time = &segment.analysis.estimated_time;
+
+                writeln!(
+                    f,
+                    "    {}: {}s -> {}s, {}",
+                    segment.name,
+                    estimated_time.old.normalize(),
+                    estimated_time.new.normalize(),
+                    time_change(estimated_time),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+//! Analyzes every revision of a hltas file in a git repository, showing how the time of the route changed.
+//! - Runs the local `git` binary, which has to be in `PATH`.
+
+use std::{
+    fmt::Display,
+    io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use hltas::HLTAS;
+use thiserror::Error;
+
+use crate::analyzer::{analyze_hltas_with, AnalyzerOptions, AnalyzerResult};
+
+/// Function that analyzes every committed revision of a HLTAS file, from the oldest commit to the newest.
+/// - The revisions are the commits that changed the file, from `git log --follow` in the directory of the file,
+///   so commits from before the file was renamed or moved are kept.
+/// - Each revision is analyzed with the given [`AnalyzerOptions`][AnalyzerOptions].
+/// - A revision that fails to be read, parsed or analyzed, such as a commit deleting the file, is kept with its error.
+/// - Only fails if git can't be run, or the file isn't in a git repository.
+pub fn analyze_history(path: &Path, options: &AnalyzerOptions) -> Result<History, HistoryError> {
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| HistoryError::InvalidPath(path.to_path_buf()))?;
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    let log = git(
+        dir,
+        &[
+            // the paths are printed as is, instead of quoted when they aren't ASCII
+            "-c",
+            "core.quotePath=false",
+            "log",
+            // `--follow` misses renames with `--reverse`, so the commits are reversed after
+            "--follow",
+            "--name-only",
+            "--date=short",
+            // commits are separated by the record separator and fields by the unit separator,
+            // which can't be in names
+            "--format=%x1e%H%x1f%an%x1f%ad",
+            "--",
+            file_name,
+        ],
+    )?;
+
+    let mut revisions = Vec::new();
+    for entry in log.split('\x1e').rev() {
+        let mut lines = entry.lines();
+        let mut fields = lines.next().unwrap_or_default().split('\x1f');
+        let (Some(commit), Some(author), Some(date)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        // the path of the file in the commit, relative to the repository root
+        let Some(file_path) = lines.find(|line| !line.is_empty()) else {
+            continue;
+        };
+
+        let analysis = git(dir, &["show", &format!("{commit}:{file_path}")])
+            .map_err(|err| err.to_string())
+            .and_then(|content| HLTAS::from_str(&content).map_err(|err| err.to_string()))
+            .and_then(|hltas| analyze_hltas_with(&hltas, options).map_err(|err| err.to_string()));
+
+        revisions.push(Revision {
+            commit: commit.to_string(),
+            author: author.to_string(),
+            date: date.to_string(),
+            analysis,
+        });
+    }
+
+    Ok(History { revisions })
+}
+
+/// Runs git in the directory, returning the stdout on success.
+fn git(dir: &Path, args: &[&str]) -> Result<String, HistoryError> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+
+    if !output.status.success() {
+        return Err(HistoryError::GitFailed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Error type for the [`analyze_history`][analyze_history] function.
+#[derive(Debug, Error)]
+pub enum HistoryError {
+    /// Error when the path doesn't name a file, or the file name isn't valid UTF-8.
+    #[error("{} isn't a path to a file", .0.display())]
+    InvalidPath(PathBuf),
+    /// Error when git can't be run, usually because it isn't installed.
+    #[error("Failed to run git")]
+    GitError(#[from] io::Error),
+    /// Error when git exits with an error, with what it printed to stderr.
+    #[error("git failed: {0}")]
+    GitFailed(String),
+}
+
+/// The analysis of every revision of a HLTAS file, from [`analyze_history`][analyze_history].
+/// - The [`Display`](std::fmt::Display) output is a table of the revisions with their estimated time,
+///   and the change from the previous revision that was analyzed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct History {
+    /// The revisions from the oldest commit to the newest.
+    pub revisions: Vec<Revision>,
+}
+
+impl History {
+    /// The columns of the history table.
+    pub const COLUMNS: [&'static str; 5] = ["Commit", "Author", "Date", "Estimated time", "Delta"];
+
+    /// The cells of the history table, from the oldest commit to the newest.
+    /// - The delta is from the previous revision that was analyzed, and is `-` for the first.
+    /// - Revisions that failed have `-` for the estimated time and delta.
+    pub fn table(&self) -> Vec<[String; 5]> {
+        let mut previous = None;
+
+        self.revisions
+            .iter()
+            .map(|revision| {
+                let (estimated_time, delta) = match &revision.analysis {
+                    Ok(analysis) => {
+                        let estimated_time = analysis.estimated_time;
+                        let delta = match previous.replace(estimated_time) {
+                            Some(previous) => {
+                                format!("{:+}s", (estimated_time - previous).normalize())
+                            }
+                            None => "-".to_string(),
+                        };
+
+                        (format!("{}s", estimated_time.normalize()), delta)
+                    }
+                    Err(_) => ("-".to_string(), "-".to_string()),
+                };
+
+                [
+                    revision.short_commit().to_string(),
+                    revision.author.clone(),
+                    revision.date.clone(),
+                    estimated_time,
+                    delta,
+                ]
+            })
+            .collect()
+    }
+}
+
+/// A committed revision of a HLTAS file in a [`History`][History].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Revision {
+    /// The full commit hash.
+    pub commit: String,
+    /// The name of the commit author.
+    pub author: String,
+    /// The author date of the commit, as `YYYY-MM-DD`.
+    pub date: String,
+    /// The analysis of the file in the commit, or the error message if it failed.
+    pub analysis: Result<AnalyzerResult, String>,
+}
+
+impl Revision {
+    /// The first 7 characters of the commit hash.
+    pub fn short_commit(&self) -> &str {
+        self.commit.get(..7).unwrap_or(&self.commit)
+    }
+}
+
+impl Display for History {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let header = Self::COLUMNS;
+        let rows = self.table();
+
+        let mut widths = header.map(str::len);
+        for cells in &rows {
+            for (width, cell) in widths.iter_mut().zip(cells) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let write_row = |f: &mut std::fmt::Formatter<'_>, cells: &[String]| {
+            let mut line = String::new();
+
+            for (i, (cell, width)) in cells.iter().zip(widths).enumerate() {
+                if i > 0 {
+                    line.push_str(" | ");
+                }
+                // the times are right aligned
+                if i < 3 {
+                    line.push_str(&format!("{cell:<width$}"));
+                } else {
+                    line.push_str(&format!("{cell:>width$}"));
+                }
+            }
+
+            writeln!(f, "{}", line.trim_end())
+        };
+
+        write_row(f, &header.map(str::to_string))?;
+        writeln!(
+            f,
+            "{}",
+            widths
+                .iter()
+                .map(|width| "-".repeat(*width))
+                .collect::<Vec<_>>()
+                .join("-+-")
+        )?;
+        for cells in &rows {
+            write_row(f, cells)?;
+        }
+
+        for revision in &self.revisions {
+            if let Err(err) = &revision.analysis {
+                writeln!(f)?;
+                writeln!(f, "{} failed:", revision.short_commit())?;
+                for line in err.lines() {
+                    writeln!(f, "    {line}")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
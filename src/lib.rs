@@ -1,9 +1,29 @@
 //! # Tool that analysis a hltas file or framebulks
 //!
 //! # How to use the app
-//! - Enter a full hltas file by pasting in the console, or framebulks without the full file
+//! - Pass paths to hltas files as arguments to analyze each file, with a combined total if there are multiple files
+//!   - Directories are searched recursively for `.hltas` files, and glob patterns such as `chapters/*/*.hltas` are expanded
+//! - Or without any paths, enter a full hltas file by pasting in the console, or framebulks without the full file
 //! - Enter EOF which can be done with `ctrl+D` on linux / macOS, or `ctrl+Z` on windows
 //! - Exit by force quitting with `ctrl+c`
+//! - `--format json`, `predict` and `calibrate` need the `serde` feature, which isn't enabled by default, so install with `cargo install hltas-framebulk-analyzer --features serde` to use them
+//!
+//! # Subcommands
+//! - `normalize <path> [output path]`: Rewrites a hltas file with identical framebulks in a row merged and repeated settings lines removed, refusing to write if the times would change, and writing to stdout without an output path
+//! - `compare <old path> <new path>`: Compares two versions of a hltas file, showing the change in times, frametime stats and counters, and how much time each segment started by a `// SPLIT:` comment saves or loses
+//! - `history <path>`: Analyzes every committed revision of a hltas file in a git repository, showing a table of each commit with its author, date, estimated time and the change from the previous commit, which needs `git` to be installed
+//! - `predict <path> <bxt log path>`: Compares the predicted time of each framebulk against the real time in a bxt TAS log of the same run, showing where the 0ms ducktap estimate was most wrong, which needs the `serde` feature
+//! - `calibrate <calibration path> [--map <start map>] <bxt log path>...`: Measures the 0ms ducktap airtime of each map in bxt TAS logs, saving it to a calibration file with a `map = airtime` line for each map, where `--map` names the map the following logs start on if they don't start with a map command, which needs the `serde` feature
+//!
+//! # Options
+//! - `--summary`: Prints a table of the files with their times, frame count and save count, instead of a full analysis of each
+//! - `--format <text|json|csv|markdown>`: The output format, with multiple files or `--summary` being shown as the summary table, a single file being shown as its full analysis like stdin, and `json` requiring the `serde` feature
+//! - `--color <always|never|auto>`: Whether to colour the text output, where `auto` colours only when printing to a terminal and `NO_COLOR` isn't set
+//! - `--keep-going`: Keeps analyzing past framebulks that fail to parse, showing every error with its line index and the partial analysis of the other lines
+//! - `--lint`: Warns about suspicious lines, such as frametimes the engine clamps, likely frametime typos, 0ms ducktaps without `frametime0ms`, and `target_yaw_override` or `change` lines longer than the framebulks after them, exiting with an error code if there are any warnings
+//! - `--maps`: Shows the final time, estimated time and frame count of each map after the analysis, split at `map`, `changelevel` and `changelevel2` console commands and starting with the map in the hltas `load_command`
+//! - `--calibration <path>`: Uses the ducktap airtime from a calibration file for the map in the hltas `load_command`, switching to the next map's airtime on each map change, instead of the flat ground airtime
+//! - `--lss <path>`: Writes the segments of the hltas, split by saves, resets, `// SPLIT:` comments and map changes, as a LiveSplit splits file
 //!
 //! # Library usage example
 //! ```
@@ -59,17 +79,34 @@
 //!
 //! # Q & A
 //! - Q: Why is this colourful?
-//! - A: It looks cool thats why
+//! - A: It looks cool thats why, but `--color never` turns it off
 //!
 //! - Q: Why so many stats are printed on the console? It seems pointless
 //! - A: Idk more the better I guess
 //!
 //! - Q: Why not analyze the bxt logs directly instead if hltas only gives an estimated time?
 //! - A: That's why I'm making an analyzer that will take bxt logs, and maybe hltas together to made a better analysis of the TAS
+//!   - The `bxt_log` module now reads the logs written by `bxt_tas_write_log`, giving the real time of the run
 
 pub mod analyzer;
+#[cfg(feature = "serde")]
+pub mod bxt_log;
+pub mod calibration;
+pub mod compare;
+pub mod history;
+pub mod lint;
+pub mod livesplit;
+pub mod normalize;
+#[cfg(feature = "serde")]
+pub mod prediction;
+pub mod report;
+pub mod segments;
+pub mod summary;
 
-pub use analyzer::analyze_hltas;
+pub use analyzer::{analyze_hltas, analyze_hltas_keep_going, analyze_hltas_with};
+pub use segments::analyze_hltas_segments;
 
+#[cfg(feature = "serde")]
+mod serde_biguint;
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,224 @@
+//! Lints a hltas file for suspicious lines, such as frametimes the engine would clamp.
+
+use std::fmt::Display;
+
+use hltas::{
+    types::{LeaveGroundAction, LeaveGroundActionType, Line},
+    HLTAS,
+};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use rust_decimal_macros::dec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The highest frametime before the engine clamps it.
+pub const MAX_FRAMETIME: Decimal = dec!(0.25);
+/// The lowest frametime before the engine clamps it.
+pub const MIN_FRAMETIME: Decimal = dec!(0.001);
+/// How much a frametime can differ from the frametime around it relative to that frametime, to be a likely typo.
+pub const TYPO_FRAMETIME_RATIO: Decimal = dec!(0.01);
+
+/// Function that lints a HLTAS, returning the warnings in line order.
+/// - Framebulks with a frametime that can't be parsed as a [`Decimal`](rust_decimal::Decimal) are skipped,
+///   as the analyzer already fails on them.
+pub fn lint_hltas(hltas: &HLTAS) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+
+    // frametimes of the framebulks, used to compare a frametime to the frametime around it
+    let frametimes = hltas
+        .lines
+        .iter()
+        .filter_map(|line| match line {
+            Line::FrameBulk(fb) => Some(fb.frame_time.parse::<Decimal>().ok()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let mut framebulk_index = 0;
+
+    for (line_index, line) in hltas.lines.iter().enumerate() {
+        let mut warn = |kind| warnings.push(LintWarning { line_index, kind });
+
+        match line {
+            Line::FrameBulk(fb) => {
+                let index = framebulk_index;
+                framebulk_index += 1;
+
+                if matches!(
+                    fb.auto_actions.leave_ground_action,
+                    Some(LeaveGroundAction {
+                        type_: LeaveGroundActionType::DuckTap { zero_ms: true },
+                        ..
+                    })
+                ) && hltas.properties.frametime_0ms.is_none()
+                {
+                    warn(LintWarningKind::MissingZeroMsFrametime);
+                }
+
+                let Some(frametime) = frametimes[index] else {
+                    continue;
+                };
+
+                if frametime > MAX_FRAMETIME {
+                    warn(LintWarningKind::FrametimeAboveMax { frametime });
+                } else if frametime < MIN_FRAMETIME {
+                    warn(LintWarningKind::FrametimeBelowMin { frametime });
+                }
+
+                if let Some(neighbour) = typo_neighbour(&frametimes, index) {
+                    warn(LintWarningKind::FrametimeTypo {
+                        frametime,
+                        neighbour,
+                    });
+                }
+            }
+            Line::TargetYawOverride(yaws) => {
+                // the override is replaced by the next one
+                let frames = hltas.lines[line_index + 1..]
+                    .iter()
+                    .take_while(|line| !matches!(line, Line::TargetYawOverride(_)))
+                    .filter_map(|line| match line {
+                        Line::FrameBulk(fb) => Some(u64::from(fb.frame_count.get())),
+                        _ => None,
+                    })
+                    .sum::<u64>();
+
+                if yaws.len() as u64 > frames {
+                    warn(LintWarningKind::UnusedTargetYawOverride {
+                        angle_count: yaws.len(),
+                        frame_count: frames,
+                    });
+                }
+            }
+            Line::Change(change) => {
+                // the change is replaced by the next change of the same value
+                let time = hltas.lines[line_index + 1..]
+                    .iter()
+                    .take_while(
+                        |line| !matches!(line, Line::Change(next) if next.target == change.target),
+                    )
+                    .filter_map(|line| match line {
+                        Line::FrameBulk(fb) => {
+                            let frametime = fb.frame_time.parse::<Decimal>().ok()?;
+                            Some(frametime * Decimal::from(fb.frame_count.get()))
+                        }
+                        _ => None,
+                    })
+                    .sum::<Decimal>();
+
+                if let Some(over) = Decimal::from_f32(change.over) {
+                    if over > time {
+                        warn(LintWarningKind::ChangeLongerThanFrameBulks { over, time });
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    warnings
+}
+
+/// Returns the frametime around the framebulk if the frametime of the framebulk is only slightly different to it.
+/// - The frametimes of the framebulks before and after must be the same.
+///   If there's only one of them, its frametime must be the same as the framebulk on its other side,
+///   so the framebulks next to a typo aren't warned about.
+fn typo_neighbour(frametimes: &[Option<Decimal>], index: usize) -> Option<Decimal> {
+    let frametime = frametimes[index]?;
+    let before = index.checked_sub(1).and_then(|i| frametimes[i]);
+    let after = frametimes.get(index + 1).copied().flatten();
+
+    let neighbour = match (before, after) {
+        (Some(before), Some(after)) if before == after => before,
+        (Some(before), None) if index >= 2 && frametimes[index - 2] == Some(before) => before,
+        (None, Some(after)) if frametimes.get(index + 2).copied().flatten() == Some(after) => after,
+        _ => return None,
+    };
+    let difference = (frametime - neighbour).abs();
+
+    (!difference.is_zero() && difference <= neighbour * TYPO_FRAMETIME_RATIO).then_some(neighbour)
+}
+
+/// A lint warning of a HLTAS line.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LintWarning {
+    /// The index of the line in the HLTAS lines.
+    pub line_index: usize,
+    /// What is suspicious about the line.
+    pub kind: LintWarningKind,
+}
+
+impl Display for LintWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line index {}: {}", self.line_index, self.kind)
+    }
+}
+
+/// What is suspicious about a HLTAS line.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LintWarningKind {
+    /// The frametime is above [`MAX_FRAMETIME`][MAX_FRAMETIME], which the engine clamps to.
+    FrametimeAboveMax { frametime: Decimal },
+    /// The frametime is below [`MIN_FRAMETIME`][MIN_FRAMETIME], which the engine clamps to.
+    FrametimeBelowMin { frametime: Decimal },
+    /// The frametime is only slightly different to the frametime of the framebulks around it.
+    /// - The difference is within [`TYPO_FRAMETIME_RATIO`][TYPO_FRAMETIME_RATIO] of the frametime around it.
+    FrametimeTypo {
+        frametime: Decimal,
+        /// The frametime of the framebulks around it.
+        neighbour: Decimal,
+    },
+    /// The framebulk uses 0ms ducktaps, but the `frametime0ms` property is missing.
+    MissingZeroMsFrametime,
+    /// The `target_yaw_override` line has more angles than the frames that follow it,
+    /// up to the next `target_yaw_override`.
+    UnusedTargetYawOverride {
+        angle_count: usize,
+        frame_count: u64,
+    },
+    /// The `change` line changes over a longer time than the framebulks that follow it,
+    /// up to the next `change` of the same value.
+    ChangeLongerThanFrameBulks {
+        over: Decimal,
+        /// The time of the framebulks that follow.
+        time: Decimal,
+    },
+}
+
+impl Display for LintWarningKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintWarningKind::FrametimeAboveMax { frametime } => write!(
+                f,
+                "frametime {frametime} is above {MAX_FRAMETIME}, which the engine clamps to"
+            ),
+            LintWarningKind::FrametimeBelowMin { frametime } => write!(
+                f,
+                "frametime {frametime} is below {MIN_FRAMETIME}, which the engine clamps to"
+            ),
+            LintWarningKind::FrametimeTypo {
+                frametime,
+                neighbour,
+            } => write!(
+                f,
+                "frametime {frametime} is only slightly different to the frametime {neighbour} around it, which could be a typo"
+            ),
+            LintWarningKind::MissingZeroMsFrametime => {
+                write!(f, "0ms ducktap without the frametime0ms property")
+            }
+            LintWarningKind::UnusedTargetYawOverride {
+                angle_count,
+                frame_count,
+            } => write!(
+                f,
+                "target_yaw_override has {angle_count} angles, but only {frame_count} frames follow it"
+            ),
+            LintWarningKind::ChangeLongerThanFrameBulks { over, time } => write!(
+                f,
+                "change over {over}s is longer than the {time}s of the framebulks that follow it"
+            ),
+        }
+    }
+}
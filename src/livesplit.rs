@@ -0,0 +1,105 @@
+//! Writes segments of a hltas file as a LiveSplit splits file.
+
+use std::io::{self, Write};
+
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use rust_decimal_macros::dec;
+
+use crate::segments::Segment;
+
+/// Writes the segments as a LiveSplit `.lss` splits file.
+/// - The estimated time of each segment is used for the split times of the `Personal Best` comparison.
+///   As the segment times are from the timeline of the whole HLTAS, the last split is the estimated time of the HLTAS.
+/// - The final time range of each segment is used for the `TAS Best` and `TAS Worst` comparisons,
+///   with the start of the range also being the best segment time.
+/// - Segments without a name are named by their position, starting from 1.
+pub fn write_lss<W: Write>(mut writer: W, segments: &[Segment]) -> io::Result<()> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(writer, r#"<Run version="1.7.0">"#)?;
+    writeln!(writer, "  <GameIcon />")?;
+    writeln!(writer, "  <GameName />")?;
+    writeln!(writer, "  <CategoryName />")?;
+    writeln!(writer, "  <Metadata>")?;
+    writeln!(writer, r#"    <Run id="" />"#)?;
+    writeln!(writer, r#"    <Platform usesEmulator="False" />"#)?;
+    writeln!(writer, "    <Region />")?;
+    writeln!(writer, "    <Variables />")?;
+    writeln!(writer, "  </Metadata>")?;
+    writeln!(writer, "  <Offset>00:00:00</Offset>")?;
+    writeln!(writer, "  <AttemptCount>0</AttemptCount>")?;
+    writeln!(writer, "  <AttemptHistory />")?;
+    writeln!(writer, "  <Segments>")?;
+
+    let mut estimated_time = Decimal::ZERO;
+    let mut best_time = Decimal::ZERO;
+    let mut worst_time = Decimal::ZERO;
+
+    for (i, segment) in segments.iter().enumerate() {
+        let analysis = &segment.analysis;
+
+        estimated_time += analysis.estimated_time;
+        best_time += analysis.final_time.start;
+        worst_time += analysis.final_time.end;
+
+        let name = match segment.name() {
+            Some(name) => escape_xml(name),
+            None => format!("Segment {}", i + 1),
+        };
+
+        writeln!(writer, "    <Segment>")?;
+        writeln!(writer, "      <Name>{name}</Name>")?;
+        writeln!(writer, "      <Icon />")?;
+        writeln!(writer, "      <SplitTimes>")?;
+        for (comparison, time) in [
+            ("Personal Best", estimated_time),
+            ("TAS Best", best_time),
+            ("TAS Worst", worst_time),
+        ] {
+            writeln!(writer, r#"        <SplitTime name="{comparison}">"#)?;
+            write_time(&mut writer, "          ", time)?;
+            writeln!(writer, "        </SplitTime>")?;
+        }
+        writeln!(writer, "      </SplitTimes>")?;
+        writeln!(writer, "      <BestSegmentTime>")?;
+        write_time(&mut writer, "        ", analysis.final_time.start)?;
+        writeln!(writer, "      </BestSegmentTime>")?;
+        writeln!(writer, "      <SegmentHistory />")?;
+        writeln!(writer, "    </Segment>")?;
+    }
+
+    writeln!(writer, "  </Segments>")?;
+    writeln!(writer, "  <AutoSplitterSettings />")?;
+    writeln!(writer, "</Run>")
+}
+
+fn write_time<W: Write>(writer: &mut W, indent: &str, seconds: Decimal) -> io::Result<()> {
+    let time = format_time(seconds);
+
+    writeln!(writer, "{indent}<RealTime>{time}</RealTime>")?;
+    writeln!(writer, "{indent}<GameTime>{time}</GameTime>")
+}
+
+/// Formats seconds as a LiveSplit time, which is `hh:mm:ss.fffffff`.
+fn format_time(seconds: Decimal) -> String {
+    let seconds = seconds.round_dp(7);
+    let whole = seconds.trunc();
+    // both are non-negative and small enough
+    let fraction = ((seconds - whole) * dec!(10_000_000)).to_u64().unwrap_or(0);
+    let whole = whole.to_u64().unwrap_or(0);
+
+    format!(
+        "{:02}:{:02}:{:02}.{:07}",
+        whole / 3600,
+        whole / 60 % 60,
+        whole % 60,
+        fraction
+    )
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
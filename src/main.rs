@@ -1,47 +1,735 @@
-use std::io::{self, Cursor, Read};
+use std::{
+    env, fs,
+    fs::File,
+    io::{self, BufWriter, Cursor, IsTerminal, Read, Write},
+    path::{Path, PathBuf},
+    process,
+};
 
+use ansi_term::Colour::{Cyan, Yellow};
 use hltas::HLTAS;
-use hltas_framebulk_analyzer::analyzer::analyze_hltas;
+use hltas_framebulk_analyzer::{
+    analyzer::{analyze_hltas_keep_going, analyze_hltas_with, AnalyzerOptions, AnalyzerResult},
+    calibration::Calibration,
+    compare::compare_hltas,
+    history::analyze_history,
+    lint::{lint_hltas, LintWarning},
+    livesplit::write_lss,
+    normalize::write_normalized_hltas,
+    report::{Csv, Markdown, Text},
+    segments::{analyze_hltas_segments, Segment, SegmentOptions},
+    summary::{Summary, SummaryFailure, SummaryRow},
+};
+#[cfg(feature = "serde")]
+use hltas_framebulk_analyzer::{bxt_log::read_bxt_log, prediction::compare_prediction};
 
 fn main() {
+    let mut args = env::args().skip(1).peekable();
+
+    // subcommands
+    let subcommand = match args.peek().map(String::as_str) {
+        Some("normalize") => Some(normalize as fn(_) -> _),
+        Some("compare") => Some(compare as fn(_) -> _),
+        Some("history") => Some(history as fn(_) -> _),
+        #[cfg(feature = "serde")]
+        Some("predict") => Some(predict as fn(_) -> _),
+        #[cfg(feature = "serde")]
+        Some("calibrate") => Some(calibrate as fn(_) -> _),
+        #[cfg(not(feature = "serde"))]
+        Some(name @ ("predict" | "calibrate")) => {
+            eprintln!("{name} requires the serde feature");
+            process::exit(1);
+        }
+        _ => None,
+    };
+    if let Some(subcommand) = subcommand {
+        args.next();
+        if let Err(err) = subcommand(args) {
+            eprintln!("{err}");
+            process::exit(1);
+        }
+        return;
+    }
+
+    let args = match Args::parse(args) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(1);
+        }
+    };
+
+    let success = if args.paths.is_empty() {
+        analyze_stdin(&args)
+    } else {
+        analyze_files(&args)
+    };
+
+    if !success {
+        process::exit(1);
+    }
+}
+
+/// Analyzes hltas files or framebulks pasted in stdin, until stdin is closed.
+/// - Returns `false` if there were any lint warnings with `--lint`.
+fn analyze_stdin(args: &Args) -> bool {
     let bar = "=".repeat(60);
+    let mut success = true;
 
     loop {
         // wait for input
         let mut input = String::new();
         io::stdin().read_to_string(&mut input).unwrap();
 
-        println!("\n\n{bar}");
+        // nothing left to read, stdin is closed
+        if input.is_empty() {
+            break success;
+        }
+
+        if args.format == Format::Text {
+            println!("\n\n{bar}");
+        }
 
         // parse input
-        let hltas = match HLTAS::from_str(&input) {
+        let hltas = match parse_hltas(&input) {
             Ok(hltas) => hltas,
-            Err(_) => {
-                // attempt to parse as section of hltas
-                let hltas = hltas_header_on_framebulks_str(&input);
-
-                match HLTAS::from_str(&hltas) {
-                    Ok(hltas) => hltas,
-                    Err(err) => {
-                        // print error and loop continue
-                        println!("{}", err);
-                        continue;
-                    }
-                }
+            Err(err) => {
+                // print error and loop continue
+                println!("{}", err);
+                continue;
             }
         };
 
         // analyze hltas
-        let analysis = match analyze_hltas(&hltas) {
-            Ok(analysis) => analysis,
-            Err(err) => {
-                println!("{}", err);
+        let (analysis, errors) = analyze(&hltas, args);
+        for err in &errors {
+            println!("{}", err);
+        }
+        let Some(analysis) = analysis else {
+            continue;
+        };
+
+        // print analysis
+        print_analysis(&analysis, args);
+
+        if args.maps {
+            print_maps("stdin", &map_segments(&hltas, args), args);
+        }
+
+        if args.lint {
+            let warnings = lint_hltas(&hltas);
+            print_lint_warnings("stdin", &warnings, args);
+            success &= warnings.is_empty();
+        }
+
+        if args.format == Format::Text {
+            println!("{bar}\n\n");
+        }
+
+        if let Some(path) = &args.lss {
+            if let Err(err) = write_lss_file(&[hltas], path, args) {
+                println!("Failed to write {}: {}", path.display(), err);
+            }
+        }
+    }
+}
+
+/// Analyzes each hltas file in the arguments, printing a report for each and a combined total,
+/// or a summary table with `--summary`.
+/// - Returns `false` if any of the files failed, or had lint warnings with `--lint`.
+fn analyze_files(args: &Args) -> bool {
+    let bar = "=".repeat(60);
+    let mut summary = Summary::default();
+    let mut hltases = Vec::new();
+    let mut lint_warning_count = 0;
+
+    for path in expand_paths(&args.paths) {
+        let path = match path {
+            Ok(path) => path,
+            Err(failure) => {
+                if !args.summary && args.format == Format::Text {
+                    println!("{bar}\n{}\n{bar}\n{}\n", failure.name, failure.error);
+                }
+                summary.failures.push(failure);
                 continue;
             }
         };
+        let name = path.display().to_string();
 
-        // print analysis
-        println!("{}{bar}\n\n", analysis);
+        // the analysis is partial if there are errors with `--keep-going`
+        let (analysis, error, lint_warnings, maps) = match read_hltas(&path) {
+            Ok(hltas) => {
+                let lint_warnings = if args.lint {
+                    lint_hltas(&hltas)
+                } else {
+                    Vec::new()
+                };
+                let (analysis, errors) = analyze(&hltas, args);
+                if errors.is_empty() {
+                    let maps = if args.maps {
+                        map_segments(&hltas, args)
+                    } else {
+                        Vec::new()
+                    };
+                    hltases.push(hltas);
+                    (analysis, None, lint_warnings, maps)
+                } else {
+                    (analysis, Some(errors.join("\n")), lint_warnings, Vec::new())
+                }
+            }
+            Err(err) => (None, Some(err), Vec::new(), Vec::new()),
+        };
+
+        if !args.summary && args.format == Format::Text {
+            println!("{bar}\n{name}\n{bar}");
+            if let Some(err) = &error {
+                println!("{err}\n");
+            }
+            if let Some(analysis) = &analysis {
+                println!("{}", args.text(analysis));
+            }
+        }
+
+        if !maps.is_empty() {
+            print_maps(&name, &maps, args);
+        }
+
+        if !lint_warnings.is_empty() {
+            print_lint_warnings(&name, &lint_warnings, args);
+            lint_warning_count += lint_warnings.len();
+        }
+
+        match (analysis, error) {
+            (_, Some(error)) => summary.failures.push(SummaryFailure { name, error }),
+            (Some(analysis), None) => summary.rows.push(SummaryRow { name, analysis }),
+            (None, None) => unreachable!("analysis without a result or an error"),
+        }
+    }
+
+    if args.format != Format::Text {
+        // a single file is shown like stdin, as the summary table would leave out most of the analysis
+        match (summary.rows.as_slice(), summary.failures.as_slice()) {
+            ([row], []) if !args.summary => print_analysis(&row.analysis, args),
+            ([], [failure]) if !args.summary => eprintln!("{}: {}", failure.name, failure.error),
+            _ => print_summary(&summary, args),
+        }
+    } else if args.summary {
+        print!("{}", args.text(&summary));
+    } else if summary.rows.len() > 1 {
+        let total = summary.total();
+
+        println!("{bar}");
+        println!("Total of {} files", summary.rows.len());
+        println!("{bar}");
+        println!("{}", args.text(&total));
+    }
+
+    let mut success = summary.failures.is_empty() && lint_warning_count == 0;
+
+    if let Some(path) = &args.lss {
+        if let Err(err) = write_lss_file(&hltases, path, args) {
+            println!("Failed to write {}: {}", path.display(), err);
+            success = false;
+        }
+    }
+
+    success
+}
+
+/// The segments of the hltas split only on map changes, for the per-map breakdown with `--maps`.
+/// - Empty if the analysis fails, as the error is already shown with the analysis.
+fn map_segments(hltas: &HLTAS, args: &Args) -> Vec<Segment> {
+    analyze_hltas_segments(
+        hltas,
+        &SegmentOptions::map_changes(),
+        &args.analyzer_options(hltas),
+    )
+    .unwrap_or_default()
+}
+
+/// Prints the per-map breakdown of a hltas after its report, or to stderr if the output isn't the text report.
+fn print_maps(name: &str, maps: &[Segment], args: &Args) {
+    if args.summary || args.format != Format::Text {
+        for map in maps {
+            eprintln!("{name}: {map}");
+        }
+        return;
+    }
+
+    let title = "Maps";
+    if args.colour {
+        println!("{}: {}", Cyan.paint(title), maps.len());
+    } else {
+        println!("{title}: {}", maps.len());
+    }
+    for map in maps {
+        println!("    {map}");
+    }
+    println!();
+}
+
+/// Prints the lint warnings of a hltas after its report, or to stderr if the output isn't the text report.
+fn print_lint_warnings(name: &str, warnings: &[LintWarning], args: &Args) {
+    if args.summary || args.format != Format::Text {
+        for warning in warnings {
+            eprintln!("{name}: {warning}");
+        }
+        return;
+    }
+
+    let title = "Lint warnings";
+    if args.colour {
+        println!("{}: {}", Yellow.paint(title), warnings.len());
+    } else {
+        println!("{title}: {}", warnings.len());
+    }
+    for warning in warnings {
+        println!("    {warning}");
+    }
+    println!();
+}
+
+/// Compares the predicted times of a hltas file against a bxt log of the same run.
+/// - Arguments are `<path> <bxt log path>`.
+#[cfg(feature = "serde")]
+fn predict(args: impl Iterator<Item = String>) -> Result<(), String> {
+    let args = args.collect::<Vec<_>>();
+    let [path, log_path] = args.as_slice() else {
+        return Err("predict requires a path and a bxt log path".to_string());
+    };
+
+    let hltas = read_hltas(Path::new(path))?;
+    let log = File::open(log_path).map_err(|err| err.to_string())?;
+    let log = read_bxt_log(io::BufReader::new(log)).map_err(|err| err.to_string())?;
+
+    let report =
+        compare_prediction(&hltas, &log, &Default::default()).map_err(|err| err.to_string())?;
+    print!("{report}");
+
+    Ok(())
+}
+
+/// Calibrates the ducktap airtime of each map from bxt logs, saving it to a calibration file.
+/// - Arguments are `<calibration path> [--map <start map>] <bxt log path>...`,
+///   where `--map` sets the map the following logs start on, for logs that don't start with a map command.
+/// - Maps already in the calibration file are kept, unless the logs have the map.
+/// - Fails without touching the file if no ducktaps were measured.
+#[cfg(feature = "serde")]
+fn calibrate(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    let usage = "calibrate requires a calibration path and bxt log paths";
+    let path = args.next().ok_or(usage)?;
+    let path = Path::new(&path);
+
+    let mut logs = Vec::new();
+    let mut start_map = None;
+    while let Some(arg) = args.next() {
+        if arg == "--map" {
+            start_map = Some(args.next().ok_or("--map requires a map name")?);
+            continue;
+        }
+
+        let log = File::open(&arg).map_err(|err| format!("{arg}: {err}"))?;
+        let log = read_bxt_log(io::BufReader::new(log)).map_err(|err| format!("{arg}: {err}"))?;
+        logs.push((log, start_map.clone()));
+    }
+    if logs.is_empty() {
+        return Err(usage.to_string());
+    }
+
+    let measured = Calibration::from_logs(
+        logs.iter()
+            .map(|(log, start_map)| (log, start_map.as_deref())),
+    );
+    if measured.ducktap_airtimes.is_empty() {
+        return Err("No 0ms ducktaps were measured in the bxt logs, use --map <start map> if the logs don't start with a map command".to_string());
+    }
+
+    let mut calibration = if path.exists() {
+        read_calibration(path)?
+    } else {
+        Calibration::default()
+    };
+    for (map, airtime) in &measured.ducktap_airtimes {
+        println!("{map}: {airtime}s");
+    }
+    calibration
+        .ducktap_airtimes
+        .extend(measured.ducktap_airtimes);
+
+    fs::write(path, calibration.to_string()).map_err(|err| err.to_string())
+}
+
+/// Normalizes a hltas file, writing it to the output path, or stdout if there isn't one.
+/// - Arguments are `<path> [output path]`, and the output path can be the same as the input.
+fn normalize(args: impl Iterator<Item = String>) -> Result<(), String> {
+    let args = args.collect::<Vec<_>>();
+    let (input, output) = match args.as_slice() {
+        [input] => (input, None),
+        [input, output] => (input, Some(output)),
+        _ => return Err("normalize requires a path, and optionally an output path".to_string()),
+    };
+
+    let hltas = read_hltas(Path::new(input))?;
+
+    // written to memory first, so nothing is written if the times changed
+    let mut normalized_hltas = Vec::new();
+    let normalized = write_normalized_hltas(&mut normalized_hltas, &hltas, &Default::default())
+        .map_err(|err| err.to_string())?;
+
+    match output {
+        Some(output) => fs::write(output, normalized_hltas),
+        None => io::stdout().write_all(&normalized_hltas),
+    }
+    .map_err(|err| err.to_string())?;
+
+    eprintln!(
+        "Merged {} framebulks and removed {} redundant lines",
+        normalized.merged_framebulk_count, normalized.removed_line_count
+    );
+
+    Ok(())
+}
+
+/// Compares the analysis of two hltas files, with the time saved or lost in each segment started by a `// SPLIT:` comment.
+/// - Arguments are `<old path> <new path>`.
+fn compare(args: impl Iterator<Item = String>) -> Result<(), String> {
+    let args = args.collect::<Vec<_>>();
+    let [old_path, new_path] = args.as_slice() else {
+        return Err("compare requires an old path and a new path".to_string());
+    };
+
+    let old = read_hltas(Path::new(old_path))?;
+    let new = read_hltas(Path::new(new_path))?;
+
+    let comparison = compare_hltas(&old, &new, &Default::default(), &Default::default())
+        .map_err(|err| err.to_string())?;
+    print!("{comparison}");
+
+    Ok(())
+}
+
+/// Shows how the estimated time of a hltas file changed over its git history.
+/// - Arguments are `<path>`, which has to be in a git repository.
+fn history(args: impl Iterator<Item = String>) -> Result<(), String> {
+    let args = args.collect::<Vec<_>>();
+    let [path] = args.as_slice() else {
+        return Err("history requires a path".to_string());
+    };
+
+    let history =
+        analyze_history(Path::new(path), &Default::default()).map_err(|err| err.to_string())?;
+    print!("{history}");
+
+    Ok(())
+}
+
+/// Analyzes the hltas, returning the analysis if there is one and the error messages.
+/// - With `keep_going`, every error is returned along with the partial analysis of the other lines.
+/// - Uses the calibrated ducktap airtime of the map with `--calibration`.
+fn analyze(hltas: &HLTAS, args: &Args) -> (Option<AnalyzerResult>, Vec<String>) {
+    let options = args.analyzer_options(hltas);
+
+    if args.keep_going {
+        let analysis = analyze_hltas_keep_going(hltas, &options);
+        let errors = analysis.errors.iter().map(ToString::to_string).collect();
+
+        (Some(analysis.result), errors)
+    } else {
+        match analyze_hltas_with(hltas, &options) {
+            Ok(analysis) => (Some(analysis), Vec::new()),
+            Err(err) => (None, vec![err.to_string()]),
+        }
+    }
+}
+
+/// Prints the analysis of a hltas in the output format.
+fn print_analysis(analysis: &AnalyzerResult, args: &Args) {
+    match args.format {
+        Format::Text => println!("{}", args.text(analysis)),
+        Format::Csv => print!("{}", Csv(analysis)),
+        Format::Markdown => print!("{}", Markdown(analysis)),
+        #[cfg(feature = "serde")]
+        Format::Json => println!("{}", serde_json::to_string_pretty(analysis).unwrap()),
+    }
+}
+
+/// Prints the summary of the files in a format other than the coloured text.
+fn print_summary(summary: &Summary, args: &Args) {
+    match args.format {
+        Format::Text => print!("{}", args.text(summary)),
+        Format::Csv => print!("{}", Csv(summary)),
+        Format::Markdown => print!("{}", Markdown(summary)),
+        #[cfg(feature = "serde")]
+        Format::Json => {
+            #[derive(serde::Serialize)]
+            struct Report<'a> {
+                #[serde(flatten)]
+                summary: &'a Summary,
+                total: AnalyzerResult,
+            }
+
+            let report = Report {
+                summary,
+                total: summary.total(),
+            };
+
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        }
+    }
+}
+
+/// Expands the paths from the arguments into hltas files.
+/// - Directories are walked recursively for `.hltas` files.
+/// - Paths that don't exist and contain `*`, `?` or `[` are used as glob patterns.
+fn expand_paths(paths: &[PathBuf]) -> Vec<Result<PathBuf, SummaryFailure>> {
+    let mut expanded = Vec::new();
+
+    for path in paths {
+        let name = path.display().to_string();
+
+        if path.is_dir() {
+            walk_dir(path, &mut expanded);
+        } else if !path.exists() && name.contains(['*', '?', '[']) {
+            match glob::glob(&name) {
+                Ok(entries) => {
+                    for entry in entries {
+                        expanded.push(entry.map_err(|err| SummaryFailure {
+                            name: err.path().display().to_string(),
+                            error: err.error().to_string(),
+                        }));
+                    }
+                }
+                Err(err) => expanded.push(Err(SummaryFailure {
+                    name,
+                    error: err.to_string(),
+                })),
+            }
+        } else {
+            expanded.push(Ok(path.clone()));
+        }
+    }
+
+    expanded
+}
+
+fn walk_dir(dir: &Path, expanded: &mut Vec<Result<PathBuf, SummaryFailure>>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            expanded.push(Err(SummaryFailure {
+                name: dir.display().to_string(),
+                error: err.to_string(),
+            }));
+            return;
+        }
+    };
+
+    let mut paths = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    for path in paths {
+        if path.is_dir() {
+            walk_dir(&path, expanded);
+        } else if path.extension().is_some_and(|ext| ext == "hltas") {
+            expanded.push(Ok(path));
+        }
+    }
+}
+
+/// Command line arguments.
+struct Args {
+    /// Path to write a LiveSplit splits file to.
+    lss: Option<PathBuf>,
+    /// Print a summary table instead of a report for each file.
+    summary: bool,
+    /// Keep analyzing past errors, showing every error and the partial analysis.
+    keep_going: bool,
+    /// Lint the hltas files, failing if there are any warnings.
+    lint: bool,
+    /// Show the time of each map in the hltas files.
+    maps: bool,
+    /// Calibrated ducktap airtimes to analyze with.
+    calibration: Option<Calibration>,
+    /// The output format.
+    format: Format,
+    /// Whether to colour the text output.
+    colour: bool,
+    /// Paths of the hltas files, directories or glob patterns to analyze, reading stdin if empty.
+    paths: Vec<PathBuf>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut lss = None;
+        let mut summary = false;
+        let mut keep_going = false;
+        let mut lint = false;
+        let mut maps = false;
+        let mut calibration = None;
+        let mut format = Format::Text;
+        let mut colour = None;
+        let mut paths = Vec::new();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--lss" => {
+                    let path = args.next().ok_or("--lss requires a path")?;
+                    lss = Some(PathBuf::from(path));
+                }
+                "--summary" => summary = true,
+                "--keep-going" => keep_going = true,
+                "--lint" => lint = true,
+                "--maps" => maps = true,
+                "--calibration" => {
+                    let path = args.next().ok_or("--calibration requires a path")?;
+                    calibration = Some(read_calibration(Path::new(&path))?);
+                }
+                "--format" => {
+                    let name = args.next().ok_or("--format requires a format")?;
+                    format = Format::from_name(&name)?;
+                }
+                "--color" => {
+                    let when = args
+                        .next()
+                        .ok_or("--color requires always, never or auto")?;
+                    colour = Self::parse_colour(&when)?;
+                }
+                _ if arg.starts_with("--color=") => {
+                    colour = Self::parse_colour(&arg["--color=".len()..])?;
+                }
+                _ if arg.starts_with("--") => return Err(format!("Unknown argument {arg}")),
+                _ => paths.push(PathBuf::from(arg)),
+            }
+        }
+
+        // auto colour is only for terminals, and never with `NO_COLOR` set
+        let colour = colour.unwrap_or_else(|| {
+            io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+        });
+
+        Ok(Self {
+            lss,
+            summary,
+            keep_going,
+            lint,
+            maps,
+            calibration,
+            format,
+            colour,
+            paths,
+        })
+    }
+
+    /// Parses the `--color` value, returning `None` for auto.
+    fn parse_colour(when: &str) -> Result<Option<bool>, String> {
+        match when {
+            "always" => Ok(Some(true)),
+            "never" => Ok(Some(false)),
+            "auto" => Ok(None),
+            _ => Err(format!(
+                "Unknown color option {when}, expected always, never or auto"
+            )),
+        }
+    }
+
+    /// The analyzer options for the hltas, with the calibrated ducktap airtime of its map if there is one.
+    fn analyzer_options(&self, hltas: &HLTAS) -> AnalyzerOptions {
+        match &self.calibration {
+            Some(calibration) => calibration.analyzer_options_for(hltas),
+            None => AnalyzerOptions::default(),
+        }
+    }
+
+    /// The text report of the value, coloured as set by the arguments.
+    fn text<'a, T>(&self, value: &'a T) -> Text<'a, T> {
+        Text {
+            value,
+            colour: self.colour,
+        }
+    }
+}
+
+/// Output format of the analysis.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Csv,
+    Markdown,
+    #[cfg(feature = "serde")]
+    Json,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "text" => Ok(Self::Text),
+            "csv" => Ok(Self::Csv),
+            "markdown" => Ok(Self::Markdown),
+            #[cfg(feature = "serde")]
+            "json" => Ok(Self::Json),
+            #[cfg(not(feature = "serde"))]
+            "json" => Err("json output requires the serde feature".to_string()),
+            _ => Err(format!("Unknown format {name}")),
+        }
+    }
+}
+
+/// Writes the segments of the hltas files, one after another, as a LiveSplit splits file.
+fn write_lss_file(hltases: &[HLTAS], path: &Path, args: &Args) -> Result<(), String> {
+    let options = SegmentOptions {
+        split_on_map_change: true,
+        ..Default::default()
+    };
+    let mut segments: Vec<Segment> = Vec::new();
+
+    for hltas in hltases {
+        segments.extend(
+            analyze_hltas_segments(hltas, &options, &args.analyzer_options(hltas))
+                .map_err(|err| err.to_string())?,
+        );
+    }
+
+    let file = File::create(path).map_err(|err| err.to_string())?;
+
+    write_lss(BufWriter::new(file), &segments).map_err(|err| err.to_string())
+}
+
+fn read_calibration(path: &Path) -> Result<Calibration, String> {
+    let calibration = fs::read_to_string(path).map_err(|err| err.to_string())?;
+
+    calibration
+        .parse::<Calibration>()
+        .map_err(|err| format!("{}: {err}", path.display()))
+}
+
+/// Reads a hltas file.
+/// - Unlike stdin, files have to be full hltas files, so the error is always from parsing the file itself.
+fn read_hltas(path: &Path) -> Result<HLTAS, String> {
+    let input = fs::read_to_string(path).map_err(|err| err.to_string())?;
+
+    HLTAS::from_str(&input).map_err(|err| err.to_string())
+}
+
+/// Parses a hltas, or framebulks without the full file.
+/// - Input starting with a `version` header is always parsed as a full file,
+///   so its errors aren't hidden by the framebulks attempt.
+fn parse_hltas(input: &str) -> Result<HLTAS, String> {
+    let has_header = input
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .is_some_and(|line| line.trim_start().starts_with("version"));
+
+    match HLTAS::from_str(input) {
+        Ok(hltas) => Ok(hltas),
+        Err(err) if has_header => Err(err.to_string()),
+        Err(_) => {
+            // attempt to parse as section of hltas
+            let hltas = hltas_header_on_framebulks_str(input);
+
+            HLTAS::from_str(&hltas).map_err(|err| err.to_string())
+        }
     }
 }
 
@@ -0,0 +1,128 @@
+//! Rewrites a hltas file by merging framebulks and removing redundant lines, without changing its times.
+
+use std::io::Write;
+
+use hltas::{types::Line, HLTAS};
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::analyzer::{
+    analyze_hltas_with, framebulks_mergeable, AnalyzerOptions, FinalTime, OwnedError,
+};
+
+/// Function that normalizes a HLTAS, returning the rewritten HLTAS.
+/// - Framebulks right after each other are merged into one if [`framebulks_mergeable`][framebulks_mergeable] allows it.
+/// - `buttons`, `lgagst_min_speed`, `vectorial_strafing` and `vectorial_strafing_constraints` lines
+///   setting the same value as the previous line of the same kind are removed, which can make more framebulks mergeable.
+/// - A `reset` line forgets the previous settings, so the settings after it are always kept.
+/// - Doesn't check if the times are the same, use [`write_normalized_hltas`][write_normalized_hltas] for that.
+pub fn normalize_hltas(hltas: &HLTAS) -> Normalized {
+    let mut lines: Vec<Line> = Vec::with_capacity(hltas.lines.len());
+    let mut merged_framebulk_count = 0;
+    let mut removed_line_count = 0;
+    // the last line of each settings kind
+    let mut settings: Vec<&Line> = Vec::new();
+
+    for line in &hltas.lines {
+        match line {
+            Line::Buttons(_)
+            | Line::LGAGSTMinSpeed(_)
+            | Line::VectorialStrafing(_)
+            | Line::VectorialStrafingConstraints(_) => {
+                match settings.iter_mut().find(|setting| {
+                    std::mem::discriminant(**setting) == std::mem::discriminant(line)
+                }) {
+                    Some(setting) if *setting == line => {
+                        removed_line_count += 1;
+                        continue;
+                    }
+                    Some(setting) => *setting = line,
+                    None => settings.push(line),
+                }
+            }
+            Line::Reset { .. } => settings.clear(),
+            Line::FrameBulk(fb) => {
+                if let Some(Line::FrameBulk(previous)) = lines.last_mut() {
+                    // too many frames to merge otherwise
+                    let frame_count = previous.frame_count.checked_add(fb.frame_count.get());
+
+                    if let Some(frame_count) =
+                        frame_count.filter(|_| framebulks_mergeable(previous, fb))
+                    {
+                        previous.frame_count = frame_count;
+                        merged_framebulk_count += 1;
+                        continue;
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        lines.push(line.clone());
+    }
+
+    Normalized {
+        hltas: HLTAS {
+            properties: hltas.properties.clone(),
+            lines,
+        },
+        merged_framebulk_count,
+        removed_line_count,
+    }
+}
+
+/// Function that normalizes a HLTAS with [`normalize_hltas`][normalize_hltas], and writes it with [`HLTAS::to_writer`](hltas::HLTAS::to_writer).
+/// - The HLTAS is analyzed before and after with the given [`AnalyzerOptions`][AnalyzerOptions],
+///   and nothing is written if the final time or estimated time changed.
+pub fn write_normalized_hltas<W: Write>(
+    writer: W,
+    hltas: &HLTAS,
+    options: &AnalyzerOptions,
+) -> Result<Normalized, NormalizeError> {
+    let normalized = normalize_hltas(hltas);
+
+    let before = analyze_hltas_with(hltas, options).map_err(|err| err.into_owned())?;
+    let after = analyze_hltas_with(&normalized.hltas, options).map_err(|err| err.into_owned())?;
+
+    if before.final_time != after.final_time || before.estimated_time != after.estimated_time {
+        return Err(NormalizeError::TimesChanged {
+            final_time: (before.final_time, after.final_time),
+            estimated_time: (before.estimated_time, after.estimated_time),
+        });
+    }
+
+    normalized
+        .hltas
+        .to_writer(writer)
+        .map_err(|err| NormalizeError::WriteError(Box::new(err)))?;
+
+    Ok(normalized)
+}
+
+/// A HLTAS rewritten by [`normalize_hltas`][normalize_hltas].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Normalized {
+    /// The rewritten HLTAS.
+    pub hltas: HLTAS,
+    /// The number of framebulks merged into the framebulk before them.
+    pub merged_framebulk_count: usize,
+    /// The number of redundant settings lines removed.
+    pub removed_line_count: usize,
+}
+
+/// Error type for the [`write_normalized_hltas`][write_normalized_hltas] function.
+#[derive(Debug, Error)]
+pub enum NormalizeError {
+    /// Error when analyzing the HLTAS, before or after normalizing.
+    #[error(transparent)]
+    AnalyzerError(#[from] OwnedError),
+    /// Error when the normalized HLTAS doesn't have the same times, with the times before and after.
+    #[error("Normalizing changed the final time from {}s ~ {}s to {}s ~ {}s, and the estimated time from {}s to {}s", final_time.0.start, final_time.0.end, final_time.1.start, final_time.1.end, estimated_time.0, estimated_time.1)]
+    TimesChanged {
+        final_time: (FinalTime, FinalTime),
+        estimated_time: (Decimal, Decimal),
+    },
+    /// Error when writing the normalized HLTAS.
+    #[error("Failed to write the normalized hltas")]
+    WriteError(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
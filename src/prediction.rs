@@ -0,0 +1,190 @@
+//! Compares the predicted times of a hltas file against a Bunnymod XT TAS log of the same run.
+//! - Requires the `serde` feature.
+
+use std::{fmt::Display, ops::Range};
+
+use hltas::{types::Line, HLTAS};
+use rust_decimal::Decimal;
+
+use crate::{
+    analyzer::{analyze_hltas_with, AnalyzerOptions, Error, FinalTime},
+    bxt_log::BxtLog,
+};
+
+/// Function that lines up each framebulk of a HLTAS with the physics frames it produced in a [`BxtLog`][BxtLog],
+/// returning the predicted and actual time of each framebulk.
+/// - Each frame of a framebulk is a physics frame of the log that isn't paused, in order.
+/// - The prediction is the analysis of the HLTAS with the given [`AnalyzerOptions`][AnalyzerOptions].
+/// - Framebulks after the log runs out of frames are left out, and counted in the report.
+/// - Only can fail if the frametime can't be parsed as a [`Decimal`](rust_decimal::Decimal).
+pub fn compare_prediction<'a>(
+    hltas: &'a HLTAS,
+    log: &BxtLog,
+    options: &AnalyzerOptions,
+) -> Result<PredictionReport, Error<'a>> {
+    let analysis = analyze_hltas_with(hltas, options)?;
+
+    // indices of the frames that aren't paused, as those are the frames the framebulks ran on
+    let frames = log
+        .physics_frames
+        .iter()
+        .enumerate()
+        .filter(|(_, frame)| !frame.paused)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+
+    let mut framebulks = Vec::new();
+    let mut previous_final_time = FinalTime::default();
+    let mut previous_estimated_time = Decimal::ZERO;
+    let mut frame_index = 0;
+
+    for entry in &analysis.timeline {
+        let Line::FrameBulk(fb) = &hltas.lines[entry.line_index] else {
+            continue;
+        };
+
+        let end = frame_index + fb.frame_count.get() as usize;
+        if end > frames.len() {
+            break;
+        }
+        let log_frames = &frames[frame_index..end];
+
+        framebulks.push(FrameBulkPrediction {
+            line_index: entry.line_index,
+            frames: frames[frame_index]..frames[end - 1] + 1,
+            predicted_range: FinalTime {
+                start: entry.final_time.start - previous_final_time.start,
+                end: entry.final_time.end - previous_final_time.end,
+            },
+            predicted_time: entry.estimated_time - previous_estimated_time,
+            actual_time: log_frames
+                .iter()
+                .map(|&i| log.physics_frames[i].frametime)
+                .sum(),
+            zero_ms_ducktap: entry.zero_ms_ducktap,
+        });
+
+        previous_final_time = entry.final_time;
+        previous_estimated_time = entry.estimated_time;
+        frame_index = end;
+    }
+
+    // every timeline entry is a framebulk
+    let missing_framebulk_count = analysis.timeline.len() - framebulks.len();
+
+    Ok(PredictionReport {
+        framebulks,
+        missing_framebulk_count,
+    })
+}
+
+/// The predicted and actual times of the framebulks of a HLTAS, from [`compare_prediction`][compare_prediction].
+/// - The [`Display`](std::fmt::Display) output lists every framebulk, the total, how many framebulks were left out,
+///   and the 5 most wrong 0ms ducktap estimates.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PredictionReport {
+    /// The framebulks in line order.
+    pub framebulks: Vec<FrameBulkPrediction>,
+    /// How many framebulks at the end of the HLTAS were left out, because the log ran out of frames.
+    pub missing_framebulk_count: usize,
+}
+
+impl PredictionReport {
+    /// The total predicted time of the framebulks.
+    pub fn predicted_time(&self) -> Decimal {
+        self.framebulks.iter().map(|fb| fb.predicted_time).sum()
+    }
+
+    /// The total actual time of the framebulks.
+    pub fn actual_time(&self) -> Decimal {
+        self.framebulks.iter().map(|fb| fb.actual_time).sum()
+    }
+
+    /// The 0ms ducktap framebulks, sorted from the most wrong estimate to the least.
+    pub fn worst_zero_ms_estimates(&self) -> Vec<&FrameBulkPrediction> {
+        let mut framebulks = self
+            .framebulks
+            .iter()
+            .filter(|fb| fb.zero_ms_ducktap)
+            .collect::<Vec<_>>();
+        framebulks.sort_by_key(|fb| std::cmp::Reverse(fb.difference().abs()));
+        framebulks
+    }
+}
+
+/// The predicted and actual time of a framebulk.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FrameBulkPrediction {
+    /// The index of the framebulk in the HLTAS lines.
+    pub line_index: usize,
+    /// The range of physics frame indices the framebulk ran on in the log, including paused frames in between.
+    pub frames: Range<usize>,
+    /// The predicted time range of the framebulk, from the final time of the analysis.
+    pub predicted_range: FinalTime,
+    /// The predicted time of the framebulk, from the estimated time of the analysis.
+    pub predicted_time: Decimal,
+    /// The actual time of the framebulk, from the frametimes of the log.
+    pub actual_time: Decimal,
+    /// If the framebulk can 0ms ducktap, which is what the estimate is guessing.
+    /// - Includes limited 0ms ducktaps carried over from an earlier framebulk, like the analysis.
+    pub zero_ms_ducktap: bool,
+}
+
+impl FrameBulkPrediction {
+    /// How much longer the actual time is than the predicted time, being negative if it's shorter.
+    pub fn difference(&self) -> Decimal {
+        self.actual_time - self.predicted_time
+    }
+}
+
+impl Display for FrameBulkPrediction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line index {}: predicted {}s, actual {}s, difference {:+}s{}",
+            self.line_index,
+            self.predicted_time.normalize(),
+            self.actual_time.normalize(),
+            self.difference().normalize(),
+            if self.zero_ms_ducktap {
+                " (0ms ducktap)"
+            } else {
+                ""
+            }
+        )
+    }
+}
+
+impl Display for PredictionReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for fb in &self.framebulks {
+            writeln!(f, "{fb}")?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "Total: predicted {}s, actual {}s, difference {:+}s",
+            self.predicted_time().normalize(),
+            self.actual_time().normalize(),
+            (self.actual_time() - self.predicted_time()).normalize()
+        )?;
+        if self.missing_framebulk_count > 0 {
+            writeln!(
+                f,
+                "{} framebulks after the end of the log were left out",
+                self.missing_framebulk_count
+            )?;
+        }
+
+        let worst = self.worst_zero_ms_estimates();
+        if !worst.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Most wrong 0ms ducktap estimates")?;
+            for fb in worst.into_iter().take(5) {
+                writeln!(f, "    {fb}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
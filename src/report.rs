@@ -0,0 +1,268 @@
+//! Text, CSV and Markdown reports of an analysis, for terminals, files, spreadsheets and GitHub comments.
+//!
+//! ```
+//! # use hltas::HLTAS;
+//! # use hltas_framebulk_analyzer::{analyze_hltas, report::{Csv, Markdown, Text}};
+//! let analysis = analyze_hltas(&HLTAS::default()).unwrap();
+//!
+//! println!("{}", Text::plain(&analysis));
+//! println!("{}", Csv(&analysis));
+//! println!("{}", Markdown(&analysis));
+//! ```
+
+use std::fmt::{Display, Formatter, Result};
+
+use crate::{analyzer::AnalyzerResult, summary::Summary};
+
+/// A text report, with or without colours, shown with [`Display`](std::fmt::Display).
+/// - With colours, this is the same as the [`Display`](std::fmt::Display) of the value itself.
+/// - Works for an [`AnalyzerResult`][AnalyzerResult] and a [`Summary`][Summary].
+pub struct Text<'a, T> {
+    /// The value to report.
+    pub value: &'a T,
+    /// Whether to use ANSI colour codes.
+    pub colour: bool,
+}
+
+impl<'a, T> Text<'a, T> {
+    /// A text report with colours.
+    pub fn coloured(value: &'a T) -> Self {
+        Self {
+            value,
+            colour: true,
+        }
+    }
+
+    /// A text report without colours.
+    pub fn plain(value: &'a T) -> Self {
+        Self {
+            value,
+            colour: false,
+        }
+    }
+}
+
+impl Display for Text<'_, AnalyzerResult> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.value.fmt_text(f, self.colour)
+    }
+}
+
+impl Display for Text<'_, Summary> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.value.fmt_text(f, self.colour)
+    }
+}
+
+/// A CSV report, shown with [`Display`](std::fmt::Display).
+/// - An [`AnalyzerResult`][AnalyzerResult] is a `section,name,value` table, with every stat as a row.
+/// - A [`Summary`][Summary] is the summary table, followed by the total row.
+pub struct Csv<'a, T>(pub &'a T);
+
+/// A Markdown report, shown with [`Display`](std::fmt::Display).
+/// - An [`AnalyzerResult`][AnalyzerResult] is a table for each section of stats.
+/// - A [`Summary`][Summary] is the summary table, followed by the total row and the failed files.
+pub struct Markdown<'a, T>(pub &'a T);
+
+impl Display for Csv<'_, AnalyzerResult> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let analysis = self.0;
+
+        writeln!(f, "section,name,value")?;
+        writeln!(f, "time,final_time_start,{}", analysis.final_time.start)?;
+        writeln!(f, "time,final_time_end,{}", analysis.final_time.end)?;
+        writeln!(f, "time,estimated_time,{}", analysis.estimated_time)?;
+        for stats in &analysis.frametime_stats {
+            writeln!(
+                f,
+                "frametime_stats,{},{}",
+                stats.frametime, stats.frame_count
+            )?;
+        }
+        for (name, count) in analysis.counts() {
+            writeln!(f, "counts,{},{}", csv_field(name), count)?;
+        }
+        for (name, usage) in analysis.auto_action_stats.entries() {
+            let name = csv_field(name);
+            writeln!(
+                f,
+                "auto_action_framebulks,{},{}",
+                name, usage.framebulk_count
+            )?;
+            writeln!(f, "auto_action_frames,{},{}", name, usage.frame_count)?;
+        }
+        for (name, usage) in analysis.strafe_stats.entries() {
+            let name = csv_field(name);
+            writeln!(f, "strafe_framebulks,{},{}", name, usage.framebulk_count)?;
+            writeln!(f, "strafe_frames,{},{}", name, usage.frame_count)?;
+        }
+        for (name, usage) in analysis.key_stats.entries() {
+            let name = csv_field(name);
+            writeln!(f, "key_frames,{},{}", name, usage.frame_count)?;
+            writeln!(f, "key_presses,{},{}", name, usage.press_count)?;
+        }
+        for stats in &analysis.console_command_stats {
+            writeln!(
+                f,
+                "console_commands,{},{}",
+                csv_field(&stats.name),
+                stats.count
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Markdown<'_, AnalyzerResult> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let analysis = self.0;
+
+        writeln!(f, "| Stat | Value |")?;
+        writeln!(f, "| --- | ---: |")?;
+        writeln!(
+            f,
+            "| Final time | {}s ~ {}s |",
+            analysis.final_time.start, analysis.final_time.end
+        )?;
+        writeln!(f, "| Estimated time | {}s |", analysis.estimated_time)?;
+        for (name, count) in analysis.counts() {
+            writeln!(f, "| {} | {} |", markdown_cell(name), count)?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "### Frametime stats")?;
+        writeln!(f)?;
+        writeln!(f, "| Frametime | Frame count |")?;
+        writeln!(f, "| ---: | ---: |")?;
+        for stats in &analysis.frametime_stats {
+            writeln!(f, "| {} | {} |", stats.frametime, stats.frame_count)?;
+        }
+
+        for (title, entries) in [
+            (
+                "Auto action stats",
+                analysis.auto_action_stats.entries().to_vec(),
+            ),
+            ("Strafe stats", analysis.strafe_stats.entries().to_vec()),
+        ] {
+            writeln!(f)?;
+            writeln!(f, "### {title}")?;
+            writeln!(f)?;
+            writeln!(f, "| Name | Framebulks | Frames |")?;
+            writeln!(f, "| --- | ---: | ---: |")?;
+            for (name, usage) in entries {
+                writeln!(
+                    f,
+                    "| {} | {} | {} |",
+                    markdown_cell(name),
+                    usage.framebulk_count,
+                    usage.frame_count
+                )?;
+            }
+        }
+
+        writeln!(f)?;
+        writeln!(f, "### Key stats")?;
+        writeln!(f)?;
+        writeln!(f, "| Key | Frames | Presses |")?;
+        writeln!(f, "| --- | ---: | ---: |")?;
+        for (name, usage) in analysis.key_stats.entries() {
+            writeln!(
+                f,
+                "| {} | {} | {} |",
+                markdown_cell(name),
+                usage.frame_count,
+                usage.press_count
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "### Console commands")?;
+        writeln!(f)?;
+        writeln!(f, "| Command | Count | First line |")?;
+        writeln!(f, "| --- | ---: | ---: |")?;
+        for stats in &analysis.console_command_stats {
+            let first = stats
+                .line_indices
+                .first()
+                .map(ToString::to_string)
+                .unwrap_or_default();
+
+            writeln!(
+                f,
+                "| {} | {} | {} |",
+                markdown_cell(&stats.name),
+                stats.count,
+                first
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Csv<'_, Summary> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let (rows, total) = self.0.table();
+
+        for cells in [Summary::COLUMNS.map(str::to_string)]
+            .iter()
+            .chain(&rows)
+            .chain([&total])
+        {
+            let cells = cells.iter().map(|cell| csv_field(cell)).collect::<Vec<_>>();
+            writeln!(f, "{}", cells.join(","))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Markdown<'_, Summary> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let (rows, total) = self.0.table();
+
+        writeln!(f, "| {} |", Summary::COLUMNS.join(" | "))?;
+        writeln!(f, "| --- | ---: | ---: | ---: | ---: |")?;
+        for cells in rows.iter().chain([&total]) {
+            let cells = cells
+                .iter()
+                .map(|cell| markdown_cell(cell))
+                .collect::<Vec<_>>();
+            writeln!(f, "| {} |", cells.join(" | "))?;
+        }
+
+        let failures = self.0.sorted_failures();
+        if !failures.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "### Failed files")?;
+            writeln!(f)?;
+            for failure in failures {
+                writeln!(f, "- `{}`", failure.name)?;
+                writeln!(f)?;
+                writeln!(f, "  ```")?;
+                for line in failure.error.lines() {
+                    writeln!(f, "  {line}")?;
+                }
+                writeln!(f, "  ```")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Quotes a CSV field if needed.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Escapes the characters that would break a Markdown table cell.
+fn markdown_cell(cell: &str) -> String {
+    cell.replace('|', "\\|")
+}
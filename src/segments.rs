@@ -0,0 +1,250 @@
+//! Splits a hltas file into segments, analyzing each segment with the times it has in the whole hltas.
+
+use std::{fmt::Display, ops::Range};
+
+use hltas::{types::Line, HLTAS};
+use num_bigint::BigUint;
+use rust_decimal::Decimal;
+
+use crate::analyzer::{
+    analyze_hltas_with, map_change, Analyzer, AnalyzerOptions, AnalyzerResult, Error, FinalTime,
+    FrameBulkTimeline,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Function that analyzes a HLTAS split into segments, returning a [`Segment`][Segment] for each on success.
+/// - The HLTAS is split at the markers enabled in `options`, and the marker itself isn't part of any segment,
+///   except for map change framebulks.
+/// - Segments without any framebulks are skipped.
+/// - Each segment is analyzed with the given [`AnalyzerOptions`][AnalyzerOptions].
+/// - The times of each segment are taken from the timeline of the whole HLTAS,
+///   so the 0ms ducktap estimation carries on between segments and the segment times add up to the total.
+/// - Only can fail if the frametime can't be parsed as a [`Decimal`](rust_decimal::Decimal).
+pub fn analyze_hltas_segments<'a>(
+    hltas: &'a HLTAS,
+    options: &SegmentOptions,
+    analyzer_options: &AnalyzerOptions,
+) -> Result<Vec<Segment>, Error<'a>> {
+    let total = analyze_hltas_with(hltas, analyzer_options)?;
+    let mut segments = Vec::new();
+
+    let mut marker = if options.split_on_map_change {
+        hltas
+            .properties
+            .load_command
+            .as_deref()
+            .and_then(map_change)
+            .map(|map| SegmentMarker::MapChange(map.to_string()))
+    } else {
+        None
+    };
+    let mut start = 0;
+    let mut analyzer = Analyzer::new(&hltas.properties, analyzer_options)?;
+    let mut has_framebulk = false;
+
+    for (line_index, line) in hltas.lines.iter().enumerate() {
+        if let Some(next_marker) = options.marker(line) {
+            let finished = std::mem::replace(
+                &mut analyzer,
+                Analyzer::new(&hltas.properties, analyzer_options)?,
+            );
+
+            if has_framebulk {
+                let lines = start..line_index;
+                segments.push(Segment {
+                    marker,
+                    analysis: with_total_times(finished.finish(), &lines, &total),
+                    lines,
+                });
+            }
+
+            marker = Some(next_marker);
+            has_framebulk = false;
+
+            // map changes happen on the framebulk, which is part of the new segment
+            if !matches!(line, Line::FrameBulk(_)) {
+                start = line_index + 1;
+                continue;
+            }
+            start = line_index;
+        }
+
+        has_framebulk |= matches!(line, Line::FrameBulk(_));
+        analyzer.line(line_index, line)?;
+    }
+
+    if has_framebulk {
+        let lines = start..hltas.lines.len();
+        segments.push(Segment {
+            marker,
+            analysis: with_total_times(analyzer.finish(), &lines, &total),
+            lines,
+        });
+    }
+
+    Ok(segments)
+}
+
+/// Replaces the times and timeline of a segment analysis with the ones of its lines in the analysis of the whole HLTAS,
+/// made relative to the start of the segment.
+fn with_total_times(
+    mut analysis: AnalyzerResult,
+    lines: &Range<usize>,
+    total: &AnalyzerResult,
+) -> AnalyzerResult {
+    let before = lines
+        .start
+        .checked_sub(1)
+        .and_then(|line_index| total.timeline_at_line(line_index));
+    let (start_frame, final_time, estimated_time) = match before {
+        Some(entry) => (
+            entry.end_frame.clone(),
+            entry.final_time,
+            entry.estimated_time,
+        ),
+        None => (BigUint::default(), FinalTime::default(), Decimal::ZERO),
+    };
+
+    analysis.timeline = total
+        .timeline
+        .iter()
+        .filter(|entry| lines.contains(&entry.line_index))
+        .map(|entry| FrameBulkTimeline {
+            line_index: entry.line_index,
+            start_frame: &entry.start_frame - &start_frame,
+            end_frame: &entry.end_frame - &start_frame,
+            final_time: FinalTime {
+                start: entry.final_time.start - final_time.start,
+                end: entry.final_time.end - final_time.end,
+            },
+            estimated_time: entry.estimated_time - estimated_time,
+            zero_ms_ducktap: entry.zero_ms_ducktap,
+        })
+        .collect();
+    if let Some(last) = analysis.timeline.last() {
+        analysis.final_time = last.final_time;
+        analysis.estimated_time = last.estimated_time;
+    }
+
+    analysis
+}
+
+/// Options for which lines split a HLTAS into segments.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SegmentOptions {
+    /// Split on `save` lines.
+    pub split_on_save: bool,
+    /// Split on `reset` lines.
+    pub split_on_reset: bool,
+    /// Split on comments starting with this prefix, ignoring leading whitespace.
+    /// - The rest of the comment is used as the name of the following segment.
+    pub split_comment_prefix: Option<String>,
+    /// Split on framebulks with a `map` or `changelevel` console command.
+    /// - The framebulk with the command starts the new segment.
+    /// - The first segment is named after the map in the `load_command` property, if there is one.
+    pub split_on_map_change: bool,
+}
+
+impl Default for SegmentOptions {
+    fn default() -> Self {
+        Self {
+            split_on_save: true,
+            split_on_reset: true,
+            split_comment_prefix: Some("SPLIT:".to_string()),
+            split_on_map_change: false,
+        }
+    }
+}
+
+impl SegmentOptions {
+    /// Options that only split on map changes, giving a per-map breakdown of the HLTAS.
+    pub fn map_changes() -> Self {
+        Self {
+            split_on_save: false,
+            split_on_reset: false,
+            split_comment_prefix: None,
+            split_on_map_change: true,
+        }
+    }
+
+    /// Returns the segment marker if the line splits the HLTAS.
+    fn marker(&self, line: &Line) -> Option<SegmentMarker> {
+        match line {
+            Line::Save(name) if self.split_on_save => Some(SegmentMarker::Save(name.clone())),
+            Line::Reset { .. } if self.split_on_reset => Some(SegmentMarker::Reset),
+            Line::FrameBulk(fb) if self.split_on_map_change => {
+                let map = map_change(fb.console_command.as_ref()?)?;
+
+                Some(SegmentMarker::MapChange(map.to_string()))
+            }
+            Line::Comment(comment) => {
+                let prefix = self.split_comment_prefix.as_ref()?;
+                let name = comment.trim_start().strip_prefix(prefix.as_str())?;
+
+                Some(SegmentMarker::Comment(name.trim().to_string()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A segment of a HLTAS.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Segment {
+    /// The marker that started the segment, or `None` for the start of the HLTAS.
+    pub marker: Option<SegmentMarker>,
+    /// The range of line indices the segment covers in the HLTAS.
+    pub lines: Range<usize>,
+    /// The analysis of the segment.
+    /// - The times and timeline are from the analysis of the whole HLTAS, relative to the start of the segment,
+    ///   so the 0ms ducktap estimation carries on from the previous segment.
+    pub analysis: AnalyzerResult,
+}
+
+impl Segment {
+    /// The name of the segment, taken from the marker that started it.
+    /// - For a `save` marker, this is the name of the save the segment continues from.
+    /// - For a map change marker, this is the name of the map.
+    pub fn name(&self) -> Option<&str> {
+        match self.marker.as_ref()? {
+            SegmentMarker::Save(name)
+            | SegmentMarker::Comment(name)
+            | SegmentMarker::MapChange(name) => Some(name),
+            SegmentMarker::Reset => None,
+        }
+    }
+}
+
+impl Display for Segment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (lines {}..{}): {}s ~ {}s, estimated {}s, {} frames",
+            self.name().unwrap_or("unnamed"),
+            self.lines.start,
+            self.lines.end,
+            self.analysis.final_time.start,
+            self.analysis.final_time.end,
+            self.analysis.estimated_time,
+            self.analysis.frame_count()
+        )
+    }
+}
+
+/// A line that splits a HLTAS into segments.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SegmentMarker {
+    /// A `save` line, with the save name.
+    Save(String),
+    /// A `reset` line.
+    Reset,
+    /// A comment matching the split prefix, with the rest of the comment as the name.
+    Comment(String),
+    /// A framebulk changing the map, with the map name.
+    MapChange(String),
+}
@@ -0,0 +1,14 @@
+//! Serializes a [`BigUint`](num_bigint::BigUint) as a decimal string, so any size is kept as is.
+
+use num_bigint::BigUint;
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+pub fn serialize<S: Serializer>(value: &BigUint, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigUint, D::Error> {
+    let s = String::deserialize(deserializer)?;
+
+    s.parse().map_err(Error::custom)
+}
@@ -0,0 +1,168 @@
+//! Summarizes the analysis of many hltas files as a table.
+
+use std::fmt::Display;
+
+use ansi_term::Colour::*;
+
+use crate::analyzer::{paint_if, AnalyzerResult};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A summary of the analysis of many HLTAS files.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Summary {
+    /// The successfully analyzed files.
+    pub rows: Vec<SummaryRow>,
+    /// The files that failed to be read, parsed or analyzed.
+    pub failures: Vec<SummaryFailure>,
+}
+
+impl Summary {
+    /// The columns of the summary table.
+    pub const COLUMNS: [&'static str; 5] = [
+        "File",
+        "Final time",
+        "Estimated time",
+        "Frame count",
+        "Save count",
+    ];
+
+    /// The combined analysis of all successfully analyzed files.
+    pub fn total(&self) -> AnalyzerResult {
+        self.rows.iter().map(|row| &row.analysis).sum()
+    }
+
+    /// The cells of the summary table, with the rows sorted by name, and the total row.
+    pub fn table(&self) -> (Vec<[String; 5]>, [String; 5]) {
+        let row_cells = |name: &str, analysis: &AnalyzerResult| {
+            [
+                name.to_string(),
+                format!(
+                    "{}s ~ {}s",
+                    analysis.final_time.start.round_dp(3),
+                    analysis.final_time.end.round_dp(3)
+                ),
+                format!("{}s", analysis.estimated_time.round_dp(3)),
+                analysis.frame_count().to_string(),
+                analysis.save_count.to_string(),
+            ]
+        };
+
+        let mut rows = self.rows.iter().collect::<Vec<_>>();
+        rows.sort_by(|a, b| a.name.cmp(&b.name));
+        let rows = rows
+            .into_iter()
+            .map(|row| row_cells(&row.name, &row.analysis))
+            .collect();
+
+        (rows, row_cells("Total", &self.total()))
+    }
+
+    /// The failures sorted by name.
+    pub fn sorted_failures(&self) -> Vec<&SummaryFailure> {
+        let mut failures = self.failures.iter().collect::<Vec<_>>();
+        failures.sort_by(|a, b| a.name.cmp(&b.name));
+        failures
+    }
+}
+
+/// A successfully analyzed file in a [`Summary`][Summary].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SummaryRow {
+    /// The name of the file, usually the path.
+    pub name: String,
+    /// The analysis of the file.
+    pub analysis: AnalyzerResult,
+}
+
+/// A file that failed in a [`Summary`][Summary].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SummaryFailure {
+    /// The name of the file, usually the path.
+    pub name: String,
+    /// The error message.
+    pub error: String,
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_text(f, true)
+    }
+}
+
+impl Summary {
+    /// Formats the summary as a text table, with or without colours.
+    pub(crate) fn fmt_text(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        colour: bool,
+    ) -> std::fmt::Result {
+        let header = Self::COLUMNS;
+        let (rows, total) = self.table();
+
+        let mut widths = header.map(str::len);
+        for cells in rows.iter().chain([&total]) {
+            for (width, cell) in widths.iter_mut().zip(cells) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let write_row = |f: &mut std::fmt::Formatter<'_>, cells: &[String]| {
+            let mut line = String::new();
+
+            for (i, (cell, width)) in cells.iter().zip(widths).enumerate() {
+                if i > 0 {
+                    line.push_str(" | ");
+                }
+                // the file name is left aligned, numbers are right aligned
+                if i == 0 {
+                    line.push_str(&format!("{cell:<width$}"));
+                } else {
+                    line.push_str(&format!("{cell:>width$}"));
+                }
+            }
+
+            writeln!(f, "{}", line.trim_end())
+        };
+        let separator = widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("-+-");
+
+        write_row(f, &header.map(str::to_string))?;
+        writeln!(f, "{separator}")?;
+        for cells in &rows {
+            write_row(f, cells)?;
+        }
+        writeln!(f, "{separator}")?;
+        write_row(f, &total)?;
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{}: {}",
+            paint_if(Green, "Analyzed files", colour),
+            self.rows.len()
+        )?;
+        writeln!(
+            f,
+            "{}: {}",
+            paint_if(Red, "Failed files", colour),
+            self.failures.len()
+        )?;
+
+        for failure in self.sorted_failures() {
+            writeln!(f, "    {}:", failure.name)?;
+            for line in failure.error.lines() {
+                writeln!(f, "        {line}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
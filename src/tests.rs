@@ -2,10 +2,22 @@ use std::num::NonZeroU32;
 
 use hltas::types::*;
 use num_bigint::{BigUint, ToBigUint};
-use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::{prelude::FromPrimitive, Decimal};
 use rust_decimal_macros::dec;
 
-use crate::analyzer::{analyze_hltas, FinalTime, FrametimeStats};
+use crate::{
+    analyzer::{
+        analyze_hltas, analyze_hltas_keep_going, analyze_hltas_with, ActionUsage, AnalyzerOptions,
+        AnalyzerResult, AutoActionStats, ConsoleCommandStats, ErrorLocation, FinalTime,
+        FrameBulkTimeline, FrametimeStats, KeyStats, KeyUsage, OwnedError, StrafeStats,
+    },
+    lint::{lint_hltas, LintWarning, LintWarningKind},
+    livesplit::write_lss,
+    normalize::{normalize_hltas, write_normalized_hltas},
+    report::{Csv, Markdown, Text},
+    segments::{analyze_hltas_segments, SegmentMarker, SegmentOptions},
+    summary::{Summary, SummaryFailure, SummaryRow},
+};
 
 #[test]
 fn final_time() {
@@ -324,3 +336,1852 @@ fn target_yaw_override_count() {
 
     assert_eq!(result.target_yaw_override_count, 2.to_biguint().unwrap());
 }
+
+#[test]
+fn timeline() {
+    let hltas = HLTAS {
+        properties: Default::default(),
+        lines: vec![
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(100).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+            Line::Comment("comment".to_string()),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.004".to_string(),
+                frame_count: NonZeroU32::new(50).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+        ],
+    };
+
+    let result = analyze_hltas(&hltas).unwrap();
+
+    assert_eq!(
+        result.timeline,
+        vec![
+            FrameBulkTimeline {
+                line_index: 0,
+                start_frame: 0.to_biguint().unwrap(),
+                end_frame: 100.to_biguint().unwrap(),
+                final_time: FinalTime {
+                    start: dec!(0.1),
+                    end: dec!(0.1),
+                },
+                estimated_time: dec!(0.1),
+                zero_ms_ducktap: false,
+            },
+            FrameBulkTimeline {
+                line_index: 2,
+                start_frame: 100.to_biguint().unwrap(),
+                end_frame: 150.to_biguint().unwrap(),
+                final_time: FinalTime {
+                    start: dec!(0.3),
+                    end: dec!(0.3),
+                },
+                estimated_time: dec!(0.3),
+                zero_ms_ducktap: false,
+            },
+        ]
+    );
+    assert_eq!(result.timeline_at_line(1), Some(&result.timeline[0]));
+    assert_eq!(result.timeline_at_line(5), Some(&result.timeline[1]));
+}
+
+#[test]
+fn segments() {
+    let hltas = HLTAS {
+        properties: Default::default(),
+        lines: vec![
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(100).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+            Line::Save("buffer".to_string()),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.004".to_string(),
+                frame_count: NonZeroU32::new(50).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+            Line::Comment(" SPLIT: ladder".to_string()),
+            Line::Comment("not a split".to_string()),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.01".to_string(),
+                frame_count: NonZeroU32::new(10).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+            Line::Reset { non_shared_seed: 0 },
+        ],
+    };
+
+    let segments =
+        analyze_hltas_segments(&hltas, &SegmentOptions::default(), &Default::default()).unwrap();
+
+    assert_eq!(segments.len(), 3);
+
+    assert_eq!(segments[0].marker, None);
+    assert_eq!(segments[0].lines, 0..1);
+    assert_eq!(segments[0].analysis.estimated_time, dec!(0.1));
+
+    assert_eq!(
+        segments[1].marker,
+        Some(SegmentMarker::Save("buffer".to_string()))
+    );
+    assert_eq!(segments[1].name(), Some("buffer"));
+    assert_eq!(segments[1].lines, 2..3);
+    assert_eq!(segments[1].analysis.estimated_time, dec!(0.2));
+
+    assert_eq!(segments[2].name(), Some("ladder"));
+    assert_eq!(segments[2].lines, 4..6);
+    assert_eq!(segments[2].analysis.estimated_time, dec!(0.1));
+    assert_eq!(segments[2].analysis.comment_count, 1.to_biguint().unwrap());
+}
+
+#[test]
+fn limited_zero_ms_ducktap() {
+    let hltas = HLTAS {
+        properties: Default::default(),
+        lines: vec![
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.01".to_string(),
+                frame_count: NonZeroU32::new(30).unwrap(),
+                auto_actions: AutoActions {
+                    leave_ground_action: Some(LeaveGroundAction {
+                        speed: LeaveGroundActionSpeed::Any,
+                        times: Times::Limited(NonZeroU32::new(2).unwrap()),
+                        type_: LeaveGroundActionType::DuckTap { zero_ms: true },
+                    }),
+                    ..Default::default()
+                },
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.01".to_string(),
+                frame_count: NonZeroU32::new(30).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.01".to_string(),
+                frame_count: NonZeroU32::new(30).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+        ],
+    };
+
+    let result = analyze_hltas(&hltas).unwrap();
+
+    assert_eq!(
+        result.final_time,
+        FinalTime {
+            start: dec!(0.88),
+            end: dec!(0.9),
+        }
+    );
+    assert_eq!(result.estimated_time, dec!(0.8800000002));
+
+    // the final time doesn't depend on the landing model
+    let options = AnalyzerOptions {
+        calibrated_ducktap_airtime: Some(dec!(0.5)),
+        ..Default::default()
+    };
+    assert_eq!(
+        analyze_hltas_with(&hltas, &options).unwrap().final_time,
+        result.final_time
+    );
+}
+
+#[test]
+fn analyzer_options() {
+    let hltas = HLTAS {
+        properties: Default::default(),
+        lines: vec![Line::FrameBulk(FrameBulk {
+            frame_time: "0.01".to_string(),
+            frame_count: NonZeroU32::new(100).unwrap(),
+            auto_actions: AutoActions {
+                leave_ground_action: Some(LeaveGroundAction {
+                    speed: LeaveGroundActionSpeed::Any,
+                    times: Times::UnlimitedWithinFrameBulk,
+                    type_: LeaveGroundActionType::DuckTap { zero_ms: true },
+                }),
+                ..Default::default()
+            },
+            movement_keys: Default::default(),
+            action_keys: Default::default(),
+            pitch: Default::default(),
+            console_command: Default::default(),
+        })],
+    };
+
+    assert_eq!(AnalyzerOptions::default().ducktap_airtime(), dec!(0.201));
+
+    // 0.3s airtime, every 31st frame is 0ms instead of every 21st
+    let options = AnalyzerOptions {
+        sv_gravity: dec!(400),
+        ducktap_height: dec!(18),
+        fall_distance: Decimal::ZERO,
+        calibrated_ducktap_airtime: None,
+        map_ducktap_airtimes: Default::default(),
+    };
+    assert_eq!(options.ducktap_airtime(), dec!(0.3));
+
+    let result = analyze_hltas_with(&hltas, &options).unwrap();
+
+    assert_eq!(result.estimated_time, dec!(0.9700000003));
+    assert_eq!(
+        analyze_hltas(&hltas).unwrap().estimated_time,
+        dec!(0.9600000004)
+    );
+}
+
+#[test]
+fn auto_action_stats() {
+    let hltas = HLTAS {
+        properties: Default::default(),
+        lines: vec![
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(100).unwrap(),
+                auto_actions: AutoActions {
+                    leave_ground_action: Some(LeaveGroundAction {
+                        speed: LeaveGroundActionSpeed::Optimal,
+                        times: Times::UnlimitedWithinFrameBulk,
+                        type_: LeaveGroundActionType::Jump,
+                    }),
+                    jump_bug: Some(JumpBug {
+                        times: Times::UnlimitedWithinFrameBulk,
+                    }),
+                    ..Default::default()
+                },
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(20).unwrap(),
+                auto_actions: AutoActions {
+                    leave_ground_action: Some(LeaveGroundAction {
+                        speed: LeaveGroundActionSpeed::Any,
+                        times: Times::Limited(NonZeroU32::new(1).unwrap()),
+                        type_: LeaveGroundActionType::DuckTap { zero_ms: true },
+                    }),
+                    duck_before_ground: Some(DuckBeforeGround {
+                        times: Times::UnlimitedWithinFrameBulk,
+                    }),
+                    ..Default::default()
+                },
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(5).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+        ],
+    };
+
+    let result = analyze_hltas(&hltas).unwrap();
+    let usage = |framebulk_count: u32, frame_count: u32| ActionUsage {
+        framebulk_count: framebulk_count.to_biguint().unwrap(),
+        frame_count: frame_count.to_biguint().unwrap(),
+    };
+
+    assert_eq!(
+        result.auto_action_stats,
+        AutoActionStats {
+            jump: usage(1, 100),
+            zero_ms_ducktap: usage(1, 20),
+            speed_any: usage(1, 20),
+            speed_optimal: usage(1, 100),
+            times_limited: usage(1, 20),
+            times_unlimited: usage(1, 100),
+            jump_bug: usage(1, 100),
+            duck_before_ground: usage(1, 20),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn strafe_stats() {
+    let hltas = HLTAS {
+        properties: Default::default(),
+        lines: vec![
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(100).unwrap(),
+                auto_actions: AutoActions {
+                    movement: Some(AutoMovement::Strafe(StrafeSettings {
+                        type_: StrafeType::MaxAccel,
+                        dir: StrafeDir::Yaw(90.0),
+                    })),
+                    ..Default::default()
+                },
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(20).unwrap(),
+                auto_actions: AutoActions {
+                    movement: Some(AutoMovement::SetYaw(90.0)),
+                    ..Default::default()
+                },
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(5).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(30).unwrap(),
+                auto_actions: AutoActions {
+                    movement: Some(AutoMovement::Strafe(StrafeSettings {
+                        type_: StrafeType::ConstYawspeed(210.0),
+                        dir: StrafeDir::LeftRight(NonZeroU32::new(2).unwrap()),
+                    })),
+                    ..Default::default()
+                },
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(40).unwrap(),
+                auto_actions: AutoActions {
+                    movement: Some(AutoMovement::Strafe(StrafeSettings {
+                        type_: StrafeType::MaxAccelYawOffset {
+                            start: 0.0,
+                            target: 10.0,
+                            accel: 1.0,
+                        },
+                        dir: StrafeDir::RightLeft(NonZeroU32::new(3).unwrap()),
+                    })),
+                    ..Default::default()
+                },
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+        ],
+    };
+
+    let result = analyze_hltas(&hltas).unwrap();
+    let usage = |framebulk_count: u32, frame_count: u32| ActionUsage {
+        framebulk_count: framebulk_count.to_biguint().unwrap(),
+        frame_count: frame_count.to_biguint().unwrap(),
+    };
+
+    assert_eq!(
+        result.strafe_stats,
+        StrafeStats {
+            no_strafe: usage(2, 25),
+            max_accel: usage(1, 100),
+            dir_yaw: usage(1, 100),
+            const_yawspeed: usage(1, 30),
+            dir_left_right: usage(1, 30),
+            max_accel_yaw_offset: usage(1, 40),
+            dir_right_left: usage(1, 40),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn key_stats() {
+    let hltas = HLTAS {
+        properties: Default::default(),
+        lines: vec![
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(100).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: MovementKeys {
+                    forward: true,
+                    ..Default::default()
+                },
+                action_keys: ActionKeys {
+                    use_: true,
+                    ..Default::default()
+                },
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(20).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: MovementKeys {
+                    forward: true,
+                    ..Default::default()
+                },
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(5).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: ActionKeys {
+                    use_: true,
+                    ..Default::default()
+                },
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+        ],
+    };
+
+    let result = analyze_hltas(&hltas).unwrap();
+    let usage = |frame_count: u32, press_count: u32| KeyUsage {
+        frame_count: frame_count.to_biguint().unwrap(),
+        press_count: press_count.to_biguint().unwrap(),
+    };
+
+    assert_eq!(
+        result.key_stats,
+        KeyStats {
+            forward: usage(120, 1),
+            use_: usage(105, 2),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn console_command_stats() {
+    let hltas = HLTAS {
+        properties: Default::default(),
+        lines: vec![
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(100).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Some("bxt_timer_start; +attack;;+attack".to_string()),
+            }),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(20).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(5).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Some("-attack;changelevel c1a0a".to_string()),
+            }),
+        ],
+    };
+
+    let result = analyze_hltas(&hltas).unwrap();
+    let stats = |name: &str, count: u32, line_indices: Vec<usize>| ConsoleCommandStats {
+        name: name.to_string(),
+        count: count.to_biguint().unwrap(),
+        line_indices,
+    };
+
+    assert_eq!(
+        result.console_command_stats,
+        vec![
+            stats("bxt_timer_start", 1, vec![0]),
+            stats("+attack", 2, vec![0]),
+            stats("-attack", 1, vec![2]),
+            stats("changelevel", 1, vec![2]),
+        ]
+    );
+    assert_eq!(
+        result.console_command_stats[2].to_string(),
+        "-attack: 1 times, first at line index 2"
+    );
+}
+
+#[test]
+fn map_segments() {
+    let hltas = HLTAS {
+        properties: Properties {
+            load_command: Some("sv_cheats 1;map c1a0".to_string()),
+            ..Default::default()
+        },
+        lines: vec![
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(100).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+            Line::Save("buffer".to_string()),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.004".to_string(),
+                frame_count: NonZeroU32::new(50).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Some("changelevel c1a0d".to_string()),
+            }),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.01".to_string(),
+                frame_count: NonZeroU32::new(10).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+        ],
+    };
+
+    let segments =
+        analyze_hltas_segments(&hltas, &SegmentOptions::map_changes(), &Default::default())
+            .unwrap();
+
+    assert_eq!(segments.len(), 2);
+
+    assert_eq!(segments[0].name(), Some("c1a0"));
+    assert_eq!(segments[0].lines, 0..2);
+    assert_eq!(segments[0].analysis.estimated_time, dec!(0.1));
+    assert_eq!(
+        segments[0].analysis.frame_count(),
+        100.to_biguint().unwrap()
+    );
+
+    assert_eq!(
+        segments[1].marker,
+        Some(SegmentMarker::MapChange("c1a0d".to_string()))
+    );
+    assert_eq!(segments[1].lines, 2..4);
+    assert_eq!(segments[1].analysis.estimated_time, dec!(0.3));
+    assert_eq!(segments[1].analysis.frame_count(), 60.to_biguint().unwrap());
+
+    // a segment after a map change uses the airtime of that map
+    let hltas = HLTAS::from_str(
+        "version 1
+load_command map c1a0
+frames
+----------|------|------|0.01|-|-|1|changelevel c1a0a
+save s1
+-----D----|------|------|0.01|-|-|100
+",
+    )
+    .unwrap();
+    let options = AnalyzerOptions {
+        calibrated_ducktap_airtime: Some(dec!(0.1)),
+        map_ducktap_airtimes: [
+            ("c1a0".to_string(), dec!(0.1)),
+            ("c1a0a".to_string(), dec!(0.3)),
+        ]
+        .into(),
+        ..Default::default()
+    };
+    let segments = analyze_hltas_segments(&hltas, &SegmentOptions::default(), &options).unwrap();
+
+    assert_eq!(segments[1].name(), Some("s1"));
+    // a 0ms ducktap every 0.3s
+    assert_eq!(segments[1].analysis.estimated_time, dec!(0.9700000003));
+
+    // the 0ms ducktap estimation carries on into the next map, so the maps add up to the total
+    let hltas = HLTAS::from_str(
+        "version 1
+load_command map c1a0
+frames
+-----D----|------|------|0.01|-|-|15
+-----D----|------|------|0.01|-|-|15|changelevel c1a0d
+",
+    )
+    .unwrap();
+    let segments =
+        analyze_hltas_segments(&hltas, &SegmentOptions::map_changes(), &Default::default())
+            .unwrap();
+    let total = analyze_hltas(&hltas).unwrap();
+
+    assert_eq!(total.estimated_time, dec!(0.2900000001));
+    assert_eq!(
+        segments
+            .iter()
+            .map(|segment| segment.analysis.estimated_time)
+            .collect::<Vec<_>>(),
+        vec![dec!(0.15), dec!(0.1400000001)]
+    );
+    assert_eq!(
+        segments[1].analysis.timeline[0].start_frame,
+        0.to_biguint().unwrap()
+    );
+    assert_eq!(
+        segments[1].analysis.timeline[0].estimated_time,
+        dec!(0.1400000001)
+    );
+}
+
+#[test]
+fn livesplit() {
+    let hltas = HLTAS {
+        properties: Default::default(),
+        lines: vec![
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(100).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+            Line::Comment(" SPLIT: <ladder>".to_string()),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.01".to_string(),
+                frame_count: NonZeroU32::new(6100).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+        ],
+    };
+
+    let segments =
+        analyze_hltas_segments(&hltas, &SegmentOptions::default(), &Default::default()).unwrap();
+    let mut lss = Vec::new();
+    write_lss(&mut lss, &segments).unwrap();
+    let lss = String::from_utf8(lss).unwrap();
+
+    assert!(lss.contains("<Name>Segment 1</Name>"));
+    assert!(lss.contains("<Name>&lt;ladder&gt;</Name>"));
+    assert!(lss.contains("<GameTime>00:00:00.1000000</GameTime>"));
+    assert!(lss.contains("<GameTime>00:01:01.1000000</GameTime>"));
+    assert!(lss.contains("<GameTime>00:01:01.0000000</GameTime>"));
+
+    // a 0ms ducktap chain crossing a split
+    let hltas = HLTAS::from_str(
+        "version 1
+frames
+-----D----|------|------|0.01|-|-|15
+// SPLIT: second half
+-----D----|------|------|0.01|-|-|15
+",
+    )
+    .unwrap();
+    let segments =
+        analyze_hltas_segments(&hltas, &SegmentOptions::default(), &Default::default()).unwrap();
+    let mut lss = Vec::new();
+    write_lss(&mut lss, &segments).unwrap();
+    let lss = String::from_utf8(lss).unwrap();
+
+    assert_eq!(
+        analyze_hltas(&hltas).unwrap().estimated_time,
+        dec!(0.2900000001)
+    );
+    let (_, last_split) = lss
+        .rsplit_once(r#"<SplitTime name="Personal Best">"#)
+        .unwrap();
+    assert!(last_split
+        .trim_start()
+        .starts_with("<RealTime>00:00:00.2900000</RealTime>"));
+}
+
+#[test]
+fn combined_results() {
+    let hltas = HLTAS {
+        properties: Default::default(),
+        lines: vec![
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(100).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Some("+attack".to_string()),
+            }),
+            Line::Save("buffer".to_string()),
+        ],
+    };
+    let hltas2 = HLTAS {
+        properties: Default::default(),
+        lines: vec![
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.004".to_string(),
+                frame_count: NonZeroU32::new(50).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Some("+attack".to_string()),
+            }),
+            Line::FrameBulk(FrameBulk {
+                frame_time: "0.001".to_string(),
+                frame_count: NonZeroU32::new(10).unwrap(),
+                auto_actions: Default::default(),
+                movement_keys: Default::default(),
+                action_keys: Default::default(),
+                pitch: Default::default(),
+                console_command: Default::default(),
+            }),
+        ],
+    };
+
+    let results = [
+        analyze_hltas(&hltas).unwrap(),
+        analyze_hltas(&hltas2).unwrap(),
+    ];
+    let total = results.iter().sum::<AnalyzerResult>();
+
+    assert_eq!(total.estimated_time, dec!(0.31));
+    assert_eq!(total.save_count, 1.to_biguint().unwrap());
+    assert_eq!(
+        total.frametime_stats,
+        vec![
+            FrametimeStats {
+                frametime: dec!(0.001),
+                frame_count: BigUint::from_u32(110).unwrap(),
+            },
+            FrametimeStats {
+                frametime: dec!(0.004),
+                frame_count: BigUint::from_u32(50).unwrap(),
+            },
+        ]
+    );
+    assert_eq!(
+        total.console_command_stats[0].count,
+        2.to_biguint().unwrap()
+    );
+    assert_eq!(
+        total.timeline[2],
+        FrameBulkTimeline {
+            line_index: 1,
+            start_frame: 150.to_biguint().unwrap(),
+            end_frame: 160.to_biguint().unwrap(),
+            final_time: FinalTime {
+                start: dec!(0.31),
+                end: dec!(0.31),
+            },
+            estimated_time: dec!(0.31),
+            zero_ms_ducktap: false,
+        }
+    );
+}
+
+#[test]
+fn summary() {
+    let hltas = HLTAS {
+        properties: Default::default(),
+        lines: vec![Line::FrameBulk(FrameBulk {
+            frame_time: "0.001".to_string(),
+            frame_count: NonZeroU32::new(100).unwrap(),
+            auto_actions: Default::default(),
+            movement_keys: Default::default(),
+            action_keys: Default::default(),
+            pitch: Default::default(),
+            console_command: Default::default(),
+        })],
+    };
+
+    let summary = Summary {
+        rows: vec![
+            SummaryRow {
+                name: "b.hltas".to_string(),
+                analysis: analyze_hltas(&hltas).unwrap(),
+            },
+            SummaryRow {
+                name: "a.hltas".to_string(),
+                analysis: analyze_hltas(&hltas).unwrap(),
+            },
+        ],
+        failures: vec![SummaryFailure {
+            name: "c.hltas".to_string(),
+            error: "failed to parse the line".to_string(),
+        }],
+    };
+
+    assert_eq!(summary.total().estimated_time, dec!(0.2));
+
+    let table = summary.to_string();
+    let lines = table.lines().collect::<Vec<_>>();
+
+    assert!(lines[2].starts_with("a.hltas | 0.100s ~ 0.100s |"));
+    assert!(lines[3].starts_with("b.hltas | 0.100s ~ 0.100s |"));
+    assert!(lines[5].starts_with("Total   | 0.200s ~ 0.200s |"));
+    assert!(table.contains("    c.hltas:\n        failed to parse the line\n"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_json_round_trip() {
+    let hltas = HLTAS {
+        properties: Default::default(),
+        lines: vec![Line::FrameBulk(FrameBulk {
+            frame_time: "0.010000001".to_string(),
+            frame_count: NonZeroU32::new(5719).unwrap(),
+            auto_actions: Default::default(),
+            movement_keys: Default::default(),
+            action_keys: Default::default(),
+            pitch: Default::default(),
+            console_command: Some("+attack".to_string()),
+        })],
+    };
+
+    let result = analyze_hltas(&hltas).unwrap();
+    let json = serde_json::to_value(&result).unwrap();
+
+    assert_eq!(json["estimated_time"], "57.190005719");
+    assert_eq!(json["frametime_stats"][0]["frame_count"], "5719");
+    assert_eq!(
+        serde_json::from_value::<AnalyzerResult>(json).unwrap(),
+        result
+    );
+}
+
+#[test]
+fn csv_and_markdown_reports() {
+    let hltas = HLTAS {
+        properties: Default::default(),
+        lines: vec![Line::FrameBulk(FrameBulk {
+            frame_time: "0.001".to_string(),
+            frame_count: NonZeroU32::new(100).unwrap(),
+            auto_actions: Default::default(),
+            movement_keys: Default::default(),
+            action_keys: Default::default(),
+            pitch: Default::default(),
+            console_command: Some("echo a,b".to_string()),
+        })],
+    };
+
+    let result = analyze_hltas(&hltas).unwrap();
+
+    let csv = Csv(&result).to_string();
+    assert!(csv.starts_with("section,name,value\ntime,final_time_start,0.100\n"));
+    assert!(csv.contains("\nframetime_stats,0.001,100\n"));
+    assert!(csv.contains("\ncounts,Save count,0\n"));
+    assert!(csv.contains("\nstrafe_frames,No strafe,100\n"));
+    assert!(csv.contains("\nconsole_commands,echo,1\n"));
+
+    let markdown = Markdown(&result).to_string();
+    assert!(markdown
+        .starts_with("| Stat | Value |\n| --- | ---: |\n| Final time | 0.100s ~ 0.100s |\n"));
+    assert!(markdown.contains("\n| 0.001 | 100 |\n"));
+    assert!(markdown.contains("\n| echo | 1 | 0 |\n"));
+
+    let summary = Summary {
+        rows: vec![SummaryRow {
+            name: "a, b.hltas".to_string(),
+            analysis: result,
+        }],
+        failures: Vec::new(),
+    };
+    assert_eq!(
+        Csv(&summary).to_string(),
+        "File,Final time,Estimated time,Frame count,Save count\n\
+         \"a, b.hltas\",0.100s ~ 0.100s,0.100s,100,0\n\
+         Total,0.100s ~ 0.100s,0.100s,100,0\n"
+    );
+}
+
+#[test]
+fn plain_text_report() {
+    let hltas =
+        HLTAS::from_str("version 1\nframes\n----------|------|------|0.001|-|-|100\n").unwrap();
+    let result = analyze_hltas(&hltas).unwrap();
+
+    let coloured = Text::coloured(&result).to_string();
+    let plain = Text::plain(&result).to_string();
+    assert!(coloured.contains('\x1b'));
+    assert!(!plain.contains('\x1b'));
+    assert_eq!(coloured, result.to_string());
+    assert!(plain.contains("Final time: 0.100s ~ 0.100s"));
+
+    let summary = Summary {
+        rows: vec![SummaryRow {
+            name: "a.hltas".to_string(),
+            analysis: result,
+        }],
+        failures: Vec::new(),
+    };
+    assert!(!Text::plain(&summary).to_string().contains('\x1b'));
+    assert!(Text::coloured(&summary).to_string().contains('\x1b'));
+}
+
+#[test]
+fn owned_error_location() {
+    let mut hltas = HLTAS::from_str(
+        "version 1\nframes\n----------|------|------|0.001|-|-|1\n// comment\n----------|------|------|0.001|-|-|1\n",
+    )
+    .unwrap();
+    if let Line::FrameBulk(fb) = &mut hltas.lines[2] {
+        fb.frame_time = "0.0a1".to_string();
+    }
+
+    let err = analyze_hltas(&hltas).unwrap_err();
+    assert_eq!(err.location(), ErrorLocation::Line(2));
+
+    let err: OwnedError = err.into();
+    drop(hltas);
+    assert_eq!(err.location(), ErrorLocation::Line(2));
+    assert!(matches!(
+        &err,
+        OwnedError::FrametimeParseError { string, line_index: 2, .. } if string == "0.0a1"
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Failed to parse frametime 0.0a1 as a decimal at line index 2"
+    );
+
+    let mut hltas =
+        HLTAS::from_str("version 1\nframes\n----------|------|------|0.001|-|-|1\n").unwrap();
+    hltas.properties.frametime_0ms = Some("0.0000000a1".to_string());
+    let err = analyze_hltas(&hltas).unwrap_err().into_owned();
+    assert_eq!(err.location(), ErrorLocation::Properties);
+    assert_eq!(
+        err.to_string(),
+        "Failed to parse 0ms frametime 0.0000000a1 as a decimal in properties"
+    );
+}
+
+#[test]
+fn keep_going_past_errors() {
+    let mut hltas = HLTAS::from_str(
+        "version 1\nframes\n----------|------|------|0.001|-|-|10\n----------|------|------|0.001|-|-|20\n----------|------|------|0.002|-|-|30\n----------|------|------|0.001|-|-|40\n",
+    )
+    .unwrap();
+    let good = analyze_hltas_with(&hltas, &AnalyzerOptions::default()).unwrap();
+    let keep_going = analyze_hltas_keep_going(&hltas, &AnalyzerOptions::default());
+    assert_eq!(keep_going.clone().into_result(), Ok(good));
+
+    for line_index in [1, 3] {
+        if let Line::FrameBulk(fb) = &mut hltas.lines[line_index] {
+            fb.frame_time = "bad".to_string();
+        }
+    }
+    hltas.properties.frametime_0ms = Some("bad0ms".to_string());
+
+    let partial = analyze_hltas_keep_going(&hltas, &AnalyzerOptions::default());
+    assert_eq!(
+        partial
+            .errors
+            .iter()
+            .map(OwnedError::location)
+            .collect::<Vec<_>>(),
+        vec![
+            ErrorLocation::Properties,
+            ErrorLocation::Line(1),
+            ErrorLocation::Line(3)
+        ]
+    );
+    assert_eq!(partial.result.final_time.start, dec!(0.070));
+    assert_eq!(partial.result.frame_count(), 40.to_biguint().unwrap());
+    assert_eq!(
+        partial
+            .result
+            .timeline
+            .iter()
+            .map(|timeline| timeline.line_index)
+            .collect::<Vec<_>>(),
+        vec![0, 2]
+    );
+    assert_eq!(partial.into_result().unwrap_err().len(), 3);
+}
+
+#[test]
+fn lint() {
+    let hltas = HLTAS::from_str(
+        "version 1
+frames
+----------|------|------|0.001|-|-|10
+----------|------|------|0.3|-|-|1
+----------|------|------|0.001|-|-|10
+----------|------|------|0.00101|-|-|10
+----------|------|------|0.001|-|-|10
+----------|------|------|0.0005|-|-|1
+----------|------|------|0.001|-|-|10
+target_yaw_override 1 2 3 4 5
+----------|------|------|0.001|-|-|2
+change yaw to 10 over 0.5 s
+-----D----|------|------|0.001|-|-|2
+target_yaw_override 1 2
+----------|------|------|0.001|-|-|2
+",
+    )
+    .unwrap();
+
+    assert_eq!(
+        lint_hltas(&hltas),
+        vec![
+            LintWarning {
+                line_index: 1,
+                kind: LintWarningKind::FrametimeAboveMax {
+                    frametime: dec!(0.3)
+                },
+            },
+            LintWarning {
+                line_index: 3,
+                kind: LintWarningKind::FrametimeTypo {
+                    frametime: dec!(0.00101),
+                    neighbour: dec!(0.001),
+                },
+            },
+            LintWarning {
+                line_index: 5,
+                kind: LintWarningKind::FrametimeBelowMin {
+                    frametime: dec!(0.0005)
+                },
+            },
+            LintWarning {
+                line_index: 7,
+                kind: LintWarningKind::UnusedTargetYawOverride {
+                    angle_count: 5,
+                    frame_count: 4,
+                },
+            },
+            LintWarning {
+                line_index: 9,
+                kind: LintWarningKind::ChangeLongerThanFrameBulks {
+                    over: dec!(0.5),
+                    time: dec!(0.004),
+                },
+            },
+            LintWarning {
+                line_index: 10,
+                kind: LintWarningKind::MissingZeroMsFrametime,
+            },
+        ]
+    );
+    assert_eq!(
+        lint_hltas(&hltas)[0].to_string(),
+        "line index 1: frametime 0.3 is above 0.25, which the engine clamps to"
+    );
+
+    let hltas = HLTAS::from_str(
+        "version 1\nframetime0ms 0.0000000001\nframes\n-----D----|------|------|0.001|-|-|2\n",
+    )
+    .unwrap();
+    assert!(lint_hltas(&hltas).is_empty());
+
+    // only the odd one out is a typo, not the framebulks at the edges next to it
+    let hltas = HLTAS::from_str(
+        "version 1
+frames
+----------|------|------|0.01|-|-|10
+----------|------|------|0.0101|-|-|10
+----------|------|------|0.01|-|-|10
+",
+    )
+    .unwrap();
+    assert_eq!(
+        lint_hltas(&hltas)
+            .into_iter()
+            .map(|warning| warning.line_index)
+            .collect::<Vec<_>>(),
+        vec![1]
+    );
+
+    let hltas = HLTAS::from_str(
+        "version 1
+frames
+----------|------|------|0.0101|-|-|10
+----------|------|------|0.01|-|-|10
+----------|------|------|0.01|-|-|10
+----------|------|------|0.0099|-|-|10
+",
+    )
+    .unwrap();
+    assert_eq!(
+        lint_hltas(&hltas),
+        vec![
+            LintWarning {
+                line_index: 0,
+                kind: LintWarningKind::FrametimeTypo {
+                    frametime: dec!(0.0101),
+                    neighbour: dec!(0.01),
+                },
+            },
+            LintWarning {
+                line_index: 3,
+                kind: LintWarningKind::FrametimeTypo {
+                    frametime: dec!(0.0099),
+                    neighbour: dec!(0.01),
+                },
+            },
+        ]
+    );
+}
+
+#[test]
+fn mergeable_framebulks() {
+    let hltas = HLTAS::from_str(
+        "version 1
+frames
+----------|------|------|0.001|-|-|10
+----------|------|------|0.001|-|-|20
+----------|------|------|0.001|-|-|30
+----------|------|------|0.004|-|-|10
+// comment
+----------|------|------|0.004|-|-|10
+----------|------|------|0.004|-|-|10|echo hi
+----------|------|------|0.004|-|-|10|echo hi
+-----d1----|------|------|0.004|-|-|10
+-----d1----|------|------|0.004|-|-|10
+-----d----|------|------|0.004|-|-|10
+-----d----|------|------|0.004|-|-|10
+",
+    )
+    .unwrap();
+
+    let result = analyze_hltas(&hltas).unwrap();
+    assert_eq!(result.mergeable_framebulks, vec![0..3, 10..12]);
+    assert!(result
+        .to_string()
+        .contains("Mergeable framebulk runs\u{1b}[0m: 2 (5 framebulks)"));
+    assert!(Text::plain(&result)
+        .to_string()
+        .contains("Mergeable framebulk runs: 2 (5 framebulks)"));
+
+    let total = [result.clone(), result].iter().sum::<AnalyzerResult>();
+    assert_eq!(total.mergeable_framebulks.len(), 4);
+}
+
+#[test]
+fn normalize() {
+    let hltas = HLTAS::from_str(
+        "version 1
+frames
+lgagstminspeed 30
+----------|------|------|0.001|-|-|10
+----------|------|------|0.001|-|-|20
+lgagstminspeed 30
+----------|------|------|0.001|-|-|30
+lgagstminspeed 40
+----------|------|------|0.001|-|-|10
+// comment
+----------|------|------|0.001|-|-|10
+reset 0
+lgagstminspeed 40
+----------|------|------|0.001|-|-|10|echo hi
+----------|------|------|0.001|-|-|10|echo hi
+",
+    )
+    .unwrap();
+
+    let normalized = normalize_hltas(&hltas);
+    assert_eq!(normalized.merged_framebulk_count, 2);
+    assert_eq!(normalized.removed_line_count, 1);
+    assert_eq!(
+        normalized.hltas,
+        HLTAS::from_str(
+            "version 1
+frames
+lgagstminspeed 30
+----------|------|------|0.001|-|-|60
+lgagstminspeed 40
+----------|------|------|0.001|-|-|10
+// comment
+----------|------|------|0.001|-|-|10
+reset 0
+lgagstminspeed 40
+----------|------|------|0.001|-|-|10|echo hi
+----------|------|------|0.001|-|-|10|echo hi
+",
+        )
+        .unwrap()
+    );
+
+    let mut written = Vec::new();
+    let written_normalized =
+        write_normalized_hltas(&mut written, &hltas, &AnalyzerOptions::default()).unwrap();
+    assert_eq!(written_normalized, normalized);
+
+    let written = HLTAS::from_str(std::str::from_utf8(&written).unwrap()).unwrap();
+    assert_eq!(written, normalized.hltas);
+    let before = analyze_hltas(&hltas).unwrap();
+    let after = analyze_hltas(&written).unwrap();
+    assert_eq!(before.final_time, after.final_time);
+    assert_eq!(before.estimated_time, after.estimated_time);
+    assert_eq!(after.mergeable_framebulks, Vec::new());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn bxt_log() {
+    use crate::bxt_log::{parse_bxt_log, PlayerState};
+
+    let log = parse_bxt_log(
+        r#"{
+            "tool_ver": "abc123",
+            "game_mod": "valve",
+            "phys_frames": [
+                {"ft": 0.1, "pause": true, "cbuf": "map c1a0\n"},
+                {"ft": 0.004, "cmdframes": [{"ms": 4, "btns": 4, "prepm": {"pos": [0, 0, 36], "vel": [0, 0, 0], "og": true}, "postpm": {"pos": [0, 0, 36], "vel": [10, 0, 0], "og": true}}]},
+                {"ft": 0.0000000001, "cmdframes": [{"ms": 0, "postpm": {"pos": [0, 0, 36], "vel": [10, 0, 268]}}]},
+                {"ft": 0.004, "cmdframes": [{"ms": 4, "postpm": {"pos": [0.04, 0, 37.072], "vel": [10, 0, 264.8]}}], "unused": 1}
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(log.tool_version.as_deref(), Some("abc123"));
+    assert_eq!(log.game_mod.as_deref(), Some("valve"));
+    assert_eq!(log.physics_frames.len(), 4);
+
+    let frame = &log.physics_frames[0];
+    assert!(frame.paused);
+    assert_eq!(frame.command_buffer.as_deref(), Some("map c1a0\n"));
+    assert_eq!(frame.player_state(), None);
+
+    let frame = &log.physics_frames[1];
+    assert_eq!(frame.frametime, dec!(0.004));
+    assert_eq!(frame.command_frames[0].buttons, 4);
+    assert_eq!(
+        frame.command_frames[0].pre_player_move,
+        Some(PlayerState {
+            position: [0.0, 0.0, 36.0],
+            velocity: [0.0, 0.0, 0.0],
+            on_ground: true,
+        })
+    );
+    assert_eq!(frame.velocity(), Some([10.0, 0.0, 0.0]));
+    assert_eq!(frame.on_ground(), Some(true));
+
+    let frame = &log.physics_frames[2];
+    assert_eq!(frame.frametime, dec!(0.0000000001));
+    assert_eq!(frame.command_frames[0].msec, 0);
+    assert_eq!(frame.on_ground(), Some(false));
+    assert_eq!(log.physics_frames[3].position(), Some([0.04, 0.0, 37.072]));
+
+    assert_eq!(log.real_time(), dec!(0.0080000001));
+    let analysis = log.analysis();
+    assert_eq!(
+        analysis.final_time,
+        FinalTime {
+            start: dec!(0.0080000001),
+            end: dec!(0.0080000001),
+        }
+    );
+    assert_eq!(analysis.estimated_time, dec!(0.0080000001));
+    assert_eq!(
+        analysis.frametime_stats,
+        vec![
+            FrametimeStats {
+                frametime: dec!(0.0000000001),
+                frame_count: 1.to_biguint().unwrap(),
+            },
+            FrametimeStats {
+                frametime: dec!(0.004),
+                frame_count: 2.to_biguint().unwrap(),
+            },
+        ]
+    );
+
+    assert!(parse_bxt_log("not json").is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn prediction() {
+    use crate::{
+        bxt_log::{BxtLog, PhysicsFrame},
+        prediction::compare_prediction,
+    };
+
+    let hltas = HLTAS::from_str(
+        "version 1
+frametime0ms 0.0000000001
+frames
+----------|------|------|0.004|-|-|2
+-----D----|------|------|0.004|-|-|100
+// comment
+----------|------|------|0.01|-|-|1
+----------|------|------|0.01|-|-|1
+",
+    )
+    .unwrap();
+
+    let frame = |frametime, paused| PhysicsFrame {
+        frametime,
+        paused,
+        ..Default::default()
+    };
+    let mut physics_frames = vec![frame(dec!(0.004), false); 2];
+    physics_frames.push(frame(dec!(0.1), true));
+    physics_frames.extend(vec![frame(dec!(0.0000000001), false); 10]);
+    physics_frames.extend(vec![frame(dec!(0.004), false); 90]);
+    physics_frames.push(frame(dec!(0.01), false));
+    let log = BxtLog {
+        physics_frames,
+        ..Default::default()
+    };
+
+    let report = compare_prediction(&hltas, &log, &AnalyzerOptions::default()).unwrap();
+    let analysis = analyze_hltas(&hltas).unwrap();
+
+    // the last framebulk didn't make it into the log
+    assert_eq!(report.framebulks.len(), 3);
+    assert_eq!(
+        report
+            .framebulks
+            .iter()
+            .map(|fb| (fb.line_index, fb.frames.clone(), fb.actual_time))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, 0..2, dec!(0.008)),
+            (1, 3..103, dec!(0.360000001)),
+            (3, 103..104, dec!(0.01)),
+        ]
+    );
+
+    let zero_ms = &report.framebulks[1];
+    assert!(zero_ms.zero_ms_ducktap);
+    assert_eq!(
+        zero_ms.predicted_time,
+        analysis.timeline[1].estimated_time - analysis.timeline[0].estimated_time
+    );
+    assert_eq!(
+        zero_ms.difference(),
+        zero_ms.actual_time - zero_ms.predicted_time
+    );
+    assert_eq!(report.framebulks[0].predicted_time, dec!(0.008));
+    assert_eq!(report.framebulks[0].difference(), Decimal::ZERO);
+    assert_eq!(report.predicted_time(), analysis.timeline[2].estimated_time);
+    assert_eq!(report.actual_time(), dec!(0.378000001));
+    assert_eq!(report.worst_zero_ms_estimates(), vec![zero_ms]);
+
+    let text = report.to_string();
+    assert!(text.starts_with("line index 0: predicted 0.008s, actual 0.008s, difference +0s\n"));
+    assert!(text.contains("\nMost wrong 0ms ducktap estimates\n    line index 1: "));
+    assert_eq!(report.missing_framebulk_count, 1);
+    assert!(text.contains("\n1 framebulks after the end of the log were left out\n"));
+
+    // limited 0ms ducktaps carry over to framebulks without an action
+    let hltas = HLTAS::from_str(
+        "version 1
+frames
+-----D1----|------|------|0.004|-|-|2
+----------|------|------|0.004|-|-|2
+-----d----|------|------|0.004|-|-|2
+",
+    )
+    .unwrap();
+    let log = BxtLog {
+        physics_frames: vec![frame(dec!(0.004), false); 6],
+        ..Default::default()
+    };
+
+    let report = compare_prediction(&hltas, &log, &AnalyzerOptions::default()).unwrap();
+    assert_eq!(
+        report
+            .framebulks
+            .iter()
+            .map(|fb| fb.zero_ms_ducktap)
+            .collect::<Vec<_>>(),
+        vec![true, true, false]
+    );
+    assert_eq!(report.missing_framebulk_count, 0);
+    assert!(!report.to_string().contains("left out"));
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn calibration() {
+    use crate::{
+        bxt_log::{BxtLog, CommandFrame, PhysicsFrame, PlayerState},
+        calibration::{measure_ducktap_airtimes, Calibration, CalibrationError},
+    };
+
+    let frame = |msec, on_ground, command_buffer: Option<&str>| PhysicsFrame {
+        frametime: Decimal::from(msec) / dec!(1000),
+        command_buffer: command_buffer.map(str::to_string),
+        command_frames: vec![CommandFrame {
+            msec,
+            post_player_move: Some(PlayerState {
+                on_ground,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let ducktap = |airborne_frames| {
+        let mut frames = vec![frame(0, false, None)];
+        frames.extend(vec![frame(10, false, None); airborne_frames]);
+        frames.push(frame(10, true, None));
+        frames
+    };
+
+    let mut physics_frames = vec![frame(10, true, Some("map c1a0"))];
+    physics_frames.extend(ducktap(19));
+    physics_frames.extend(ducktap(20));
+    // the command the game runs for a level transition
+    physics_frames.push(frame(
+        10,
+        true,
+        Some("echo\nchangelevel2 c1a0a c1a0_to_c1a0a"),
+    ));
+    physics_frames.extend(ducktap(18));
+    let log = BxtLog {
+        physics_frames,
+        ..Default::default()
+    };
+
+    let samples = measure_ducktap_airtimes(&log, None);
+    assert_eq!(
+        samples
+            .iter()
+            .map(|sample| (sample.map.as_str(), sample.frame_index, sample.airtime))
+            .collect::<Vec<_>>(),
+        vec![
+            ("c1a0", 1, dec!(0.2)),
+            ("c1a0", 22, dec!(0.21)),
+            ("c1a0a", 45, dec!(0.19)),
+        ]
+    );
+
+    // without a map command, the start map is needed to know the map
+    let log_without_map = BxtLog {
+        physics_frames: ducktap(19),
+        ..Default::default()
+    };
+    assert!(measure_ducktap_airtimes(&log_without_map, None).is_empty());
+    assert_eq!(
+        measure_ducktap_airtimes(&log_without_map, Some("c1a1"))[0].map,
+        "c1a1"
+    );
+
+    let calibration = Calibration::from_logs([(&log, None)]);
+    assert_eq!(
+        calibration.ducktap_airtimes.into_iter().collect::<Vec<_>>(),
+        vec![
+            ("c1a0".to_string(), dec!(0.205)),
+            ("c1a0a".to_string(), dec!(0.19)),
+        ]
+    );
+
+    // config file round trip
+    let calibration = "c1a0 = 0.205\n\n# comment\n  c1a0a=0.19  \n"
+        .parse::<Calibration>()
+        .unwrap();
+    assert_eq!(calibration.to_string().parse(), Ok(calibration.clone()));
+    assert_eq!(
+        "c1a0 = 0.205\nc1a1 0.2\n".parse::<Calibration>(),
+        Err(CalibrationError::InvalidLine {
+            line: "c1a1 0.2".to_string(),
+            line_index: 1,
+        })
+    );
+
+    let mut hltas = HLTAS::from_str(
+        "version 1
+frametime0ms 0.0000000001
+frames
+-----D----|------|------|0.01|-|-|100
+",
+    )
+    .unwrap();
+    assert_eq!(
+        calibration.analyzer_options_for(&hltas).ducktap_airtime(),
+        dec!(0.201)
+    );
+
+    hltas.properties.load_command = Some("map c1a0a".to_string());
+    let options = calibration.analyzer_options_for(&hltas);
+    assert_eq!(options.ducktap_airtime(), dec!(0.19));
+    assert_eq!(
+        analyze_hltas_with(&hltas, &options).unwrap().estimated_time,
+        // a ducktap every 0.19s instead of every 0.201s
+        dec!(0.9500000005)
+    );
+    assert_eq!(
+        analyze_hltas(&hltas).unwrap().estimated_time,
+        dec!(0.9600000004)
+    );
+
+    // the airtime switches to the next map's on a map change
+    hltas.lines.push(
+        HLTAS::from_str(
+            "version 1
+frames
+-----D----|------|------|0.01|-|-|100|changelevel2 c1a0 c1a0a_to_c1a0
+",
+        )
+        .unwrap()
+        .lines
+        .remove(0),
+    );
+    let options = calibration.analyzer_options_for(&hltas);
+    let without_switch = AnalyzerOptions {
+        map_ducktap_airtimes: Default::default(),
+        ..options.clone()
+    };
+    assert_eq!(options.map_ducktap_airtime("c1a0"), dec!(0.205));
+    assert_eq!(options.map_ducktap_airtime("c1a1"), dec!(0.201));
+    assert_eq!(
+        analyze_hltas_with(&hltas, &options).unwrap().estimated_time,
+        dec!(1.9100000009)
+    );
+    assert_eq!(
+        analyze_hltas_with(&hltas, &without_switch)
+            .unwrap()
+            .estimated_time,
+        dec!(1.9000000010)
+    );
+}
+
+#[test]
+fn compare() {
+    use crate::compare::{compare_hltas, Change, CounterComparison, FrametimeStatsComparison};
+
+    let old = HLTAS::from_str(
+        "version 1
+frames
+----------|------|------|0.01|-|-|10
+// SPLIT: section 1
+----------|------|------|0.01|-|-|10
+save s1
+----------|------|------|0.004|-|-|100
+// SPLIT: section 3
+----------|------|------|0.01|-|-|50
+",
+    )
+    .unwrap();
+    let new = HLTAS::from_str(
+        "version 1
+frames
+----------|------|------|0.01|-|-|10
+// SPLIT: section 1
+----------|------|------|0.01|-|-|10
+save s1
+// SPLIT: section 2
+----------|------|------|0.004|-|-|100
+// SPLIT: section 3
+----------|------|------|0.01|-|-|37
+",
+    )
+    .unwrap();
+
+    let comparison = compare_hltas(
+        &old,
+        &new,
+        &SegmentOptions::default(),
+        &AnalyzerOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(comparison.total.estimated_time.difference(), dec!(-0.13));
+    assert_eq!(
+        comparison.total.final_time.difference(),
+        FinalTime {
+            start: dec!(-0.13),
+            end: dec!(-0.13),
+        }
+    );
+    assert_eq!(
+        comparison.total.frametime_stats,
+        vec![
+            FrametimeStatsComparison {
+                frametime: dec!(0.004),
+                frame_count: Change {
+                    old: 100u32.into(),
+                    new: 100u32.into(),
+                },
+            },
+            FrametimeStatsComparison {
+                frametime: dec!(0.01),
+                frame_count: Change {
+                    old: 70u32.into(),
+                    new: 57u32.into(),
+                },
+            },
+        ]
+    );
+    assert!(comparison.total.counters.contains(&CounterComparison {
+        name: "Comment count".to_string(),
+        count: Change {
+            old: 2u32.into(),
+            new: 3u32.into(),
+        },
+    }));
+    assert_eq!(
+        comparison
+            .total
+            .counters
+            .iter()
+            .find(|counter| counter.name == "Frame count")
+            .unwrap()
+            .count
+            .difference(),
+        (-13).into()
+    );
+
+    // section 2 is only in the new hltas
+    assert_eq!(
+        comparison
+            .segments
+            .iter()
+            .map(|segment| (
+                segment.name.as_str(),
+                segment.analysis.estimated_time.difference()
+            ))
+            .collect::<Vec<_>>(),
+        vec![("section 1", dec!(-0.4)), ("section 3", dec!(-0.13))]
+    );
+
+    assert_eq!(
+        comparison.to_string(),
+        "Final time: 1.1s ~ 1.1s -> 0.97s ~ 0.97s (-0.13s ~ -0.13s)
+Estimated time: 1.1s -> 0.97s (saves 0.13s)
+
+Frametime stats
+    0.01s: 70 -> 57 frames (-13)
+
+Counters
+    Frame count: 170 -> 157 (-13)
+    Comment count: 2 -> 3 (+1)
+    Strafe stats: No strafe frames: 170 -> 157 (-13)
+
+Segments
+    section 1: 0.5s -> 0.1s, saves 0.4s
+    section 3: 0.5s -> 0.37s, saves 0.13s
+"
+    );
+
+    // a save inside a section doesn't cut it short
+    let old = HLTAS::from_str(
+        "version 1
+frames
+// SPLIT: a
+----------|------|------|0.01|-|-|10
+----------|------|------|0.01|-|-|10
+",
+    )
+    .unwrap();
+    let new = HLTAS::from_str(
+        "version 1
+frames
+// SPLIT: a
+----------|------|------|0.01|-|-|10
+save x
+----------|------|------|0.01|-|-|5
+",
+    )
+    .unwrap();
+
+    let comparison = compare_hltas(
+        &old,
+        &new,
+        &SegmentOptions::default(),
+        &AnalyzerOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(comparison.segments.len(), 1);
+    assert_eq!(
+        comparison.segments[0].analysis.estimated_time,
+        Change {
+            old: dec!(0.2),
+            new: dec!(0.15),
+        }
+    );
+}
+
+#[test]
+fn history() {
+    use std::{fs, process::Command};
+
+    use crate::history::analyze_history;
+
+    let dir = std::env::temp_dir().join(format!(
+        "hltas-framebulk-analyzer-history-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("route")).unwrap();
+
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(&dir)
+            .args(["-c", "commit.gpgsign=false"])
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    let commit = |author: &str, date: &str, contents: &str| {
+        fs::write(dir.join("route/c1a0.hltas"), contents).unwrap();
+        git(&["add", "-A"]);
+        git(&[
+            "-c",
+            &format!("user.name={author}"),
+            "-c",
+            "user.email=runner@example.com",
+            "commit",
+            "-q",
+            "-m",
+            "update route",
+            &format!("--date={date}T12:00:00"),
+        ]);
+    };
+
+    git(&["init", "-q"]);
+    commit(
+        "Alice",
+        "2022-01-01",
+        "version 1\nframes\n----------|------|------|0.01|-|-|100\n",
+    );
+    commit(
+        "Bob",
+        "2022-02-01",
+        "version 1\nframes\n----------|------|------|0.01|-|-|87\n",
+    );
+    // a commit that doesn't touch the hltas file
+    fs::write(dir.join("README.md"), "notes").unwrap();
+    git(&["add", "-A"]);
+    git(&[
+        "-c",
+        "user.name=Carol",
+        "-c",
+        "user.email=runner@example.com",
+        "commit",
+        "-q",
+        "-m",
+        "notes",
+    ]);
+    commit("Alice", "2022-03-01", "not a hltas file");
+    commit(
+        "Alice",
+        "2022-04-01",
+        "version 1\nframes\n----------|------|------|0.01|-|-|90\n",
+    );
+
+    let history =
+        analyze_history(&dir.join("route/c1a0.hltas"), &AnalyzerOptions::default()).unwrap();
+
+    // the revisions from before a rename still resolve
+    fs::create_dir_all(dir.join("chapters")).unwrap();
+    git(&["mv", "route/c1a0.hltas", "chapters/unforeseen.hltas"]);
+    git(&[
+        "-c",
+        "user.name=Carol",
+        "-c",
+        "user.email=runner@example.com",
+        "commit",
+        "-q",
+        "-m",
+        "rename",
+    ]);
+    let renamed_history = analyze_history(
+        &dir.join("chapters/unforeseen.hltas"),
+        &AnalyzerOptions::default(),
+    )
+    .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(history.revisions.len(), 4);
+    assert!(history.revisions[2].analysis.is_err());
+    assert_eq!(
+        history
+            .table()
+            .into_iter()
+            .map(|[_, author, date, estimated_time, delta]| [author, date, estimated_time, delta])
+            .collect::<Vec<_>>(),
+        vec![
+            ["Alice", "2022-01-01", "1s", "-"],
+            ["Bob", "2022-02-01", "0.87s", "-0.13s"],
+            ["Alice", "2022-03-01", "-", "-"],
+            ["Alice", "2022-04-01", "0.9s", "+0.03s"],
+        ]
+        .into_iter()
+        .map(|row| row.map(str::to_string))
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        history.to_string().lines().next(),
+        Some("Commit  | Author | Date       | Estimated time |  Delta")
+    );
+
+    assert_eq!(renamed_history.revisions.len(), 5);
+    assert_eq!(
+        renamed_history.revisions[..4]
+            .iter()
+            .map(|revision| (&revision.commit, &revision.analysis))
+            .collect::<Vec<_>>(),
+        history
+            .revisions
+            .iter()
+            .map(|revision| (&revision.commit, &revision.analysis))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        renamed_history.revisions[4]
+            .analysis
+            .as_ref()
+            .unwrap()
+            .estimated_time,
+        dec!(0.9)
+    );
+}